@@ -0,0 +1,117 @@
+use std::{
+    fmt,
+    sync::{Condvar, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use crate::priority_queue::{Compare, MaxComparator, PriorityQueue};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopError {
+    Timeout,
+    Closed,
+}
+
+impl fmt::Display for PopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PopError::Timeout => write!(f, "timed out waiting for an element"),
+            PopError::Closed => write!(f, "queue is closed and empty"),
+        }
+    }
+}
+
+impl std::error::Error for PopError {}
+
+struct State<T, C> {
+    queue: PriorityQueue<T, C>,
+    closed: bool,
+}
+
+// После close новые элементы не принимаются, но оставшиеся можно забрать
+pub struct ConcurrentPriorityQueue<T, C = MaxComparator> {
+    state: Mutex<State<T, C>>,
+    available: Condvar,
+}
+
+impl<T: Ord> ConcurrentPriorityQueue<T> {
+    pub fn new() -> Self {
+        ConcurrentPriorityQueue::from_queue(PriorityQueue::new())
+    }
+}
+
+impl<T, C: Compare<T>> ConcurrentPriorityQueue<T, C> {
+    pub fn from_queue(queue: PriorityQueue<T, C>) -> Self {
+        ConcurrentPriorityQueue {
+            state: Mutex::new(State {
+                queue,
+                closed: false,
+            }),
+            available: Condvar::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().queue.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().queue.len()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    // Возвращает значение обратно, если очередь уже закрыта
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let mut state = self.lock();
+        if state.closed {
+            return Err(value);
+        }
+        state.queue.push(value);
+        drop(state);
+
+        self.available.notify_one();
+        Ok(())
+    }
+
+    // Блокирует поток, пока не появится элемент; None - очередь закрыта и пуста
+    pub fn pop(&self) -> Option<T> {
+        let state = self.lock();
+        let mut state = self
+            .available
+            .wait_while(state, |state| state.queue.is_empty() && !state.closed)
+            .unwrap();
+        state.queue.pop()
+    }
+
+    pub fn pop_timeout(&self, timeout: Duration) -> Result<T, PopError> {
+        let state = self.lock();
+        let (mut state, _) = self
+            .available
+            .wait_timeout_while(state, timeout, |state| {
+                state.queue.is_empty() && !state.closed
+            })
+            .unwrap();
+
+        match state.queue.pop() {
+            Some(value) => Ok(value),
+            None if state.closed => Err(PopError::Closed),
+            None => Err(PopError::Timeout),
+        }
+    }
+
+    pub fn try_pop(&self) -> Option<T> {
+        self.lock().queue.pop()
+    }
+
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.available.notify_all();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T, C>> {
+        self.state.lock().unwrap()
+    }
+}
@@ -0,0 +1,366 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
+};
+
+use crate::priority_queue::{Compare, MaxComparator, PriorityQueue};
+
+pub trait Serializer<T> {
+    fn write(&self, value: &T, writer: &mut dyn Write) -> io::Result<()>;
+
+    // None означает, что записи в файле закончились
+    fn read(&self, reader: &mut dyn BufRead) -> io::Result<Option<T>>;
+}
+
+// Одно значение на строку в текстовом виде
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LineSerializer;
+
+impl<T> Serializer<T> for LineSerializer
+where
+    T: Display + FromStr,
+    T::Err: Display,
+{
+    fn write(&self, value: &T, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "{value}")
+    }
+
+    fn read(&self, reader: &mut dyn BufRead) -> io::Result<Option<T>> {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        line.trim_end_matches('\n')
+            .parse()
+            .map(Some)
+            .map_err(|err: T::Err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+    }
+}
+
+struct Run {
+    path: PathBuf,
+    reader: BufReader<File>,
+    // Сколько раз файлы сливались, чтобы получить этот
+    level: usize,
+}
+
+struct RunHead<T> {
+    value: T,
+    run: usize,
+}
+
+#[derive(Clone)]
+struct HeadComparator<C>(C);
+
+impl<T, C: Compare<T>> Compare<RunHead<T>> for HeadComparator<C> {
+    fn compare(&self, a: &RunHead<T>, b: &RunHead<T>) -> Ordering {
+        self.0.compare(&a.value, &b.value)
+    }
+}
+
+// Головы сливаемых файлов уже лежат в heads и забираются оттуда только
+// после успешного слияния, остальные значения читаются из файлов
+enum Merged<'a, T> {
+    Head(&'a T),
+    Read(T),
+}
+
+impl<T> Merged<'_, T> {
+    fn get(&self) -> &T {
+        match self {
+            Merged::Head(value) => value,
+            Merged::Read(value) => value,
+        }
+    }
+}
+
+// Сколько файлов одного уровня сливаются в один
+const FAN_IN: usize = 16;
+
+static NEXT_RUN: AtomicUsize = AtomicUsize::new(0);
+
+// Когда в памяти оказывается больше capacity элементов, они целиком сбрасываются
+// в отсортированный файл. pop сливает вершину кучи в памяти с головами файлов.
+// Файлы одного уровня сливаются по FAN_IN штук, так что открытых файлов не больше
+// FAN_IN - 1 на уровень, а уровней порядка log(n / capacity) по основанию FAN_IN
+pub struct ExternalPriorityQueue<T, S, C = MaxComparator> {
+    capacity: usize,
+    serializer: S,
+    directory: PathBuf,
+    memory: PriorityQueue<T, C>,
+    runs: Vec<Option<Run>>,
+    heads: PriorityQueue<RunHead<T>, HeadComparator<C>>,
+    len: usize,
+}
+
+impl<T: Ord, S: Serializer<T>> ExternalPriorityQueue<T, S> {
+    pub fn new(capacity: usize, serializer: S) -> Self {
+        ExternalPriorityQueue::with_comparator(capacity, serializer, MaxComparator)
+    }
+}
+
+impl<T, S: Serializer<T>, C: Compare<T> + Clone> ExternalPriorityQueue<T, S, C> {
+    pub fn with_comparator(capacity: usize, serializer: S, comparator: C) -> Self {
+        assert!(capacity > 0, "in-memory capacity must be positive");
+
+        ExternalPriorityQueue {
+            capacity,
+            serializer,
+            directory: std::env::temp_dir(),
+            memory: PriorityQueue::with_comparator(comparator.clone()),
+            runs: Vec::new(),
+            heads: PriorityQueue::with_comparator(HeadComparator(comparator)),
+            len: 0,
+        }
+    }
+
+    pub fn with_directory<P: AsRef<Path>>(mut self, directory: P) -> Self {
+        self.directory = directory.as_ref().to_path_buf();
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn spilled_runs(&self) -> usize {
+        self.runs.iter().flatten().count()
+    }
+
+    pub fn push(&mut self, value: T) -> io::Result<()> {
+        self.memory.push(value);
+        self.len += 1;
+
+        if self.memory.len() > self.capacity {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        match (self.memory.peek(), self.heads.peek()) {
+            (Some(value), Some(head)) if !self.head_first(value, head) => Some(value),
+            (_, Some(head)) => Some(&head.value),
+            (value, None) => value,
+        }
+    }
+
+    pub fn pop(&mut self) -> io::Result<Option<T>> {
+        let from_run = match (self.memory.peek(), self.heads.peek()) {
+            (Some(value), Some(head)) => self.head_first(value, head),
+            (None, Some(_)) => true,
+            (_, None) => false,
+        };
+
+        if !from_run {
+            let value = self.memory.pop();
+            self.len -= value.is_some() as usize;
+            return Ok(value);
+        }
+
+        // Если следующую голову прочитать не удалось, значение возвращается в кучу
+        let head = self.heads.pop().unwrap();
+        if let Err(err) = self.advance(head.run) {
+            self.heads.push(head);
+            return Err(err);
+        }
+        self.len -= 1;
+        Ok(Some(head.value))
+    }
+
+    fn head_first(&self, value: &T, head: &RunHead<T>) -> bool {
+        self.memory.comparator().compare(&head.value, value) == Ordering::Greater
+    }
+
+    // При ошибке значения остаются в памяти, а недописанный файл удаляется
+    fn spill(&mut self) -> io::Result<()> {
+        let empty = PriorityQueue::with_comparator(self.memory.comparator().clone());
+        let sorted = std::mem::replace(&mut self.memory, empty).into_sorted_vec();
+        let path = self.run_path();
+
+        let written = self.write_run(&path, |writer| {
+            for value in sorted.iter().rev() {
+                self.serializer.write(value, writer)?;
+            }
+            Ok(())
+        });
+        let (reader, first) = match written {
+            Ok(run) => run,
+            Err(err) => {
+                let _ = fs::remove_file(&path);
+                self.memory.extend(sorted);
+                return Err(err);
+            }
+        };
+        self.add_run(path, reader, first, 0);
+        self.compact()
+    }
+
+    // Сливает файлы уровня, на котором их набралось FAN_IN, пока такие уровни есть
+    fn compact(&mut self) -> io::Result<()> {
+        loop {
+            let mut counts = Vec::new();
+            for run in self.runs.iter().flatten() {
+                if counts.len() <= run.level {
+                    counts.resize(run.level + 1, 0);
+                }
+                counts[run.level] += 1;
+            }
+
+            let Some(level) = counts.iter().position(|&count| count >= FAN_IN) else {
+                return Ok(());
+            };
+            self.merge_level(level)?;
+        }
+    }
+
+    // Исходные файлы читаются заново с текущих позиций, поэтому при ошибке
+    // очередь остаётся нетронутой
+    fn merge_level(&mut self, level: usize) -> io::Result<()> {
+        let sources: Vec<usize> = (0..self.runs.len())
+            .filter(|&index| {
+                self.runs[index]
+                    .as_ref()
+                    .is_some_and(|run| run.level == level)
+            })
+            .collect();
+
+        let mut readers = Vec::with_capacity(sources.len());
+        for &index in &sources {
+            let run = self.runs[index].as_mut().unwrap();
+            let position = run.reader.stream_position()?;
+            let mut file = File::open(&run.path)?;
+            file.seek(SeekFrom::Start(position))?;
+            readers.push(BufReader::new(file));
+        }
+
+        let path = self.run_path();
+        let written = self.write_run(&path, |writer| self.merge_runs(&sources, readers, writer));
+        let (reader, first) = match written {
+            Ok(run) => run,
+            Err(err) => {
+                let _ = fs::remove_file(&path);
+                return Err(err);
+            }
+        };
+
+        self.heads.retain(|head| !sources.contains(&head.run));
+        for index in sources {
+            if let Some(run) = self.runs[index].take() {
+                let _ = fs::remove_file(run.path);
+            }
+        }
+        self.add_run(path, reader, first, level + 1);
+        Ok(())
+    }
+
+    fn merge_runs(
+        &self,
+        sources: &[usize],
+        mut readers: Vec<BufReader<File>>,
+        writer: &mut dyn Write,
+    ) -> io::Result<()> {
+        let comparator = self.memory.comparator();
+        let mut merge =
+            PriorityQueue::new_by(|a: &RunHead<Merged<'_, T>>, b: &RunHead<Merged<'_, T>>| {
+                comparator.compare(a.value.get(), b.value.get())
+            });
+
+        for head in self.heads.iter() {
+            if let Some(source) = sources.iter().position(|&index| index == head.run) {
+                merge.push(RunHead {
+                    value: Merged::Head(&head.value),
+                    run: source,
+                });
+            }
+        }
+
+        while let Some(RunHead { value, run }) = merge.pop() {
+            self.serializer.write(value.get(), writer)?;
+            if let Some(next) = self.serializer.read(&mut readers[run])? {
+                merge.push(RunHead {
+                    value: Merged::Read(next),
+                    run,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn run_path(&self) -> PathBuf {
+        self.directory.join(format!(
+            "priority-queue-{}-{}.run",
+            process::id(),
+            NEXT_RUN.fetch_add(1, AtomicOrdering::Relaxed)
+        ))
+    }
+
+    // Создаёт новый файл, заполняет его и сразу читает из него первую голову
+    fn write_run<F>(&self, path: &Path, fill: F) -> io::Result<(BufReader<File>, Option<T>)>
+    where
+        F: FnOnce(&mut dyn Write) -> io::Result<()>,
+    {
+        let file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        let mut writer = BufWriter::new(file);
+        fill(&mut writer)?;
+        writer.flush()?;
+        drop(writer);
+
+        let mut reader = BufReader::new(File::open(path)?);
+        let first = self.serializer.read(&mut reader)?;
+        Ok((reader, first))
+    }
+
+    fn add_run(&mut self, path: PathBuf, reader: BufReader<File>, first: Option<T>, level: usize) {
+        let Some(value) = first else {
+            let _ = fs::remove_file(path);
+            return;
+        };
+        self.runs.push(Some(Run {
+            path,
+            reader,
+            level,
+        }));
+        self.heads.push(RunHead {
+            value,
+            run: self.runs.len() - 1,
+        });
+    }
+
+    // Читает следующую голову файла, исчерпанный файл удаляется
+    fn advance(&mut self, index: usize) -> io::Result<()> {
+        let Some(run) = self.runs[index].as_mut() else {
+            return Ok(());
+        };
+
+        match self.serializer.read(&mut run.reader)? {
+            Some(value) => {
+                self.heads.push(RunHead { value, run: index });
+            }
+            None => {
+                if let Some(run) = self.runs[index].take() {
+                    fs::remove_file(run.path)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T, S, C> Drop for ExternalPriorityQueue<T, S, C> {
+    fn drop(&mut self) {
+        for run in self.runs.drain(..).flatten() {
+            let _ = fs::remove_file(run.path);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::priority_queue::{Compare, PriorityQueue};
+
+// Общий интерфейс очередей с приоритетом, позволяющий подменять реализацию
+pub trait Heap<T> {
+    fn push(&mut self, value: T);
+    fn pop(&mut self) -> Option<T>;
+    fn peek(&self) -> Option<&T>;
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T, C: Compare<T>, const D: usize> Heap<T> for PriorityQueue<T, C, D> {
+    fn push(&mut self, value: T) {
+        PriorityQueue::push(self, value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        PriorityQueue::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        PriorityQueue::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        PriorityQueue::len(self)
+    }
+}
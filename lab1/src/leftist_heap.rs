@@ -0,0 +1,136 @@
+use std::{cmp::Ordering, mem};
+
+use crate::{
+    heap::Heap,
+    priority_queue::{Compare, MaxComparator, MinComparator},
+};
+
+// rank - длина правого пути до пустого поддерева, у левого потомка она не меньше, чем у правого
+struct Node<T> {
+    value: T,
+    rank: usize,
+    left: Option<Box<Node<T>>>,
+    right: Option<Box<Node<T>>>,
+}
+
+pub struct LeftistHeap<T, C = MaxComparator> {
+    root: Option<Box<Node<T>>>,
+    len: usize,
+    comparator: C,
+}
+
+impl<T: Ord> LeftistHeap<T> {
+    pub fn new() -> Self {
+        LeftistHeap::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> LeftistHeap<T, MinComparator> {
+    pub fn new_min() -> Self {
+        LeftistHeap::with_comparator(MinComparator)
+    }
+}
+
+impl<T, C: Compare<T>> LeftistHeap<T, C> {
+    pub fn with_comparator(comparator: C) -> Self {
+        LeftistHeap {
+            root: None,
+            len: 0,
+            comparator,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn push(&mut self, value: T) {
+        let node = Box::new(Node {
+            value,
+            rank: 1,
+            left: None,
+            right: None,
+        });
+        let root = self.root.take();
+        self.root = self.merge(root, Some(node));
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let root = self.root.take()?;
+        let Node {
+            value, left, right, ..
+        } = *root;
+        self.root = self.merge(left, right);
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.value)
+    }
+
+    // O(log n): слияние идёт только по правым путям
+    pub fn meld(&mut self, mut other: Self) {
+        let root = self.root.take();
+        self.root = self.merge(root, other.root.take());
+        self.len += mem::take(&mut other.len);
+    }
+
+    fn rank(node: &Option<Box<Node<T>>>) -> usize {
+        node.as_ref().map_or(0, |n| n.rank)
+    }
+
+    fn merge(&self, a: Option<Box<Node<T>>>, b: Option<Box<Node<T>>>) -> Option<Box<Node<T>>> {
+        let (a, b) = match (a, b) {
+            (None, b) => return b,
+            (a, None) => return a,
+            (Some(a), Some(b)) => (a, b),
+        };
+
+        let (mut parent, child) = match self.comparator.compare(&a.value, &b.value) {
+            Ordering::Less => (b, a),
+            _ => (a, b),
+        };
+
+        parent.right = self.merge(parent.right.take(), Some(child));
+        if Self::rank(&parent.left) < Self::rank(&parent.right) {
+            mem::swap(&mut parent.left, &mut parent.right);
+        }
+        parent.rank = Self::rank(&parent.right) + 1;
+        Some(parent)
+    }
+}
+
+// Левый путь может быть длиной O(n), поэтому освобождаем дерево без рекурсии
+impl<T, C> Drop for LeftistHeap<T, C> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<T, C: Compare<T>> Heap<T> for LeftistHeap<T, C> {
+    fn push(&mut self, value: T) {
+        LeftistHeap::push(self, value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        LeftistHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        LeftistHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        LeftistHeap::len(self)
+    }
+}
@@ -1,6 +1,53 @@
+mod concurrent;
+mod external;
+mod heap;
+mod leftist_heap;
+mod median;
+mod merge;
+mod min_max_heap;
+mod pairing_heap;
+mod persistent;
 mod priority_queue;
+mod radix_heap;
+mod timer;
+mod top_k;
 
-use priority_queue::PriorityQueue;
+use std::{
+    cmp::Reverse,
+    thread,
+    time::{Duration, Instant},
+};
+
+use concurrent::{ConcurrentPriorityQueue, PopError};
+use external::{ExternalPriorityQueue, LineSerializer};
+use heap::Heap;
+use leftist_heap::LeftistHeap;
+use median::{RunningMedian, RunningQuantile};
+use merge::kmerge;
+use min_max_heap::MinMaxHeap;
+use pairing_heap::PairingHeap;
+use persistent::PersistentHeap;
+use priority_queue::{MinComparator, PriorityQueue};
+use radix_heap::RadixHeap;
+use timer::{Clock, DelayQueue, ManualClock};
+use top_k::TopK;
+
+fn fill_and_drain<H: Heap<i32>>(heap: &mut H) -> Vec<i32> {
+    for value in [5, 3, 8, 1] {
+        heap.push(value);
+    }
+    println!(
+        "Backend holds {} values, top is {:?}",
+        heap.len(),
+        heap.peek()
+    );
+
+    let mut result = Vec::new();
+    while !heap.is_empty() {
+        result.extend(heap.pop());
+    }
+    result
+}
 
 fn main() {
     let mut queue: PriorityQueue<i32> = PriorityQueue::new();
@@ -27,4 +74,338 @@ fn main() {
         Some(res) => println!("Curently max element is {res}"),
         None => println!("Queue is empty"),
     }
+
+    let mut min_queue = PriorityQueue::new_min();
+    for value in [5, 1, 4, 2, 3] {
+        min_queue.push(value);
+    }
+    if let Some(res) = min_queue.peek() {
+        println!("Min-heap top is {res}, length is {}", min_queue.len());
+    }
+
+    let mut by_deadline = PriorityQueue::new_by_key(|job: &(&str, u32)| Reverse(job.1));
+    by_deadline.push(("report", 30));
+    by_deadline.push(("backup", 10));
+    by_deadline.push(("deploy", 20));
+    while let Some((name, deadline)) = by_deadline.pop() {
+        println!("Job {name} with deadline {deadline}");
+    }
+
+    let mut by_length = PriorityQueue::new_by(|a: &&str, b: &&str| a.len().cmp(&b.len()));
+    by_length.push("pear");
+    by_length.push("fig");
+    by_length.push("banana");
+    if let Some(res) = by_length.pop() {
+        println!("Longest word is {res}");
+    }
+
+    let mut distances = PriorityQueue::new_min();
+    let a = distances.push((7, 'a'));
+    let b = distances.push((9, 'b'));
+    let c = distances.push((14, 'c'));
+
+    if let Ok(old) = distances.change_priority(c, (3, 'c')) {
+        println!("Decreased {old:?} to (3, 'c')");
+    }
+    if let Some(removed) = distances.remove(b) {
+        println!(
+            "Removed {removed:?}, handle still valid: {}",
+            distances.contains(b)
+        );
+    }
+    println!("Handle of 'a' is valid: {}", distances.contains(a));
+    while let Some((distance, vertex)) = distances.pop() {
+        println!("Vertex {vertex} at distance {distance}");
+    }
+
+    let mut heapified: PriorityQueue<i32> = PriorityQueue::from(vec![3, 8, 1, 9, 4]);
+    heapified.extend([7, 2]);
+    let mut collected: PriorityQueue<i32> = (10..15).collect();
+    heapified.append(&mut collected);
+    println!(
+        "After append length is {} (other is empty: {}), max is {:?}",
+        heapified.len(),
+        collected.is_empty(),
+        heapified.peek()
+    );
+
+    let mut events: PriorityQueue<u32, MinComparator, 4> =
+        PriorityQueue::with_comparator(MinComparator);
+    for time in [40, 10, 30, 20, 50, 5] {
+        events.push(time);
+    }
+    print!("Events from 4-ary heap:");
+    while let Some(time) = events.pop() {
+        print!(" {time}");
+    }
+    println!();
+
+    let mut leaderboard = MinMaxHeap::new();
+    for score in [70, 95, 40, 88, 61] {
+        leaderboard.push(score);
+        if leaderboard.len() > 3 {
+            leaderboard.pop_min();
+        }
+    }
+    println!(
+        "Leaderboard keeps {} scores from {:?} to {:?}",
+        leaderboard.len(),
+        leaderboard.peek_min(),
+        leaderboard.peek_max()
+    );
+    if let Some(best) = leaderboard.pop_max() {
+        println!("Best score is {best}, is empty: {}", leaderboard.is_empty());
+    }
+
+    let mut workers: PriorityQueue<u32, MinComparator> = PriorityQueue::from(vec![3, 5, 8]);
+    if let Some(mut least_loaded) = workers.peek_mut() {
+        *least_loaded += 4;
+    }
+    println!("Least loaded worker after assignment: {:?}", workers.peek());
+    if let Some(old) = workers.replace_top(1) {
+        println!(
+            "Replaced top {old}, now least loaded is {:?}",
+            workers.peek()
+        );
+    }
+
+    let mut best = TopK::largest(3);
+    best.extend((1..=1000).map(|x| (x * 7919) % 1000));
+    println!(
+        "Top {} of {} kept values: {:?}",
+        best.capacity(),
+        best.len(),
+        best.into_sorted_vec()
+    );
+
+    let mut fastest = TopK::smallest(2);
+    for lap in [125, 98, 111, 99] {
+        if let Some(dropped) = fastest.push(lap) {
+            println!("Dropped lap {dropped}");
+        }
+    }
+    println!(
+        "Fastest laps (is empty: {}): {:?}",
+        fastest.is_empty(),
+        fastest.into_sorted_vec()
+    );
+
+    let mut jobs = PriorityQueue::new_by_key(|job: &(u8, &str)| job.0).stable();
+    for job in [
+        (1, "lint"),
+        (2, "build"),
+        (1, "docs"),
+        (2, "test"),
+        (1, "format"),
+    ] {
+        jobs.push(job);
+    }
+    print!("Stable job order:");
+    while let Some((_, name)) = jobs.pop() {
+        print!(" {name}");
+    }
+    println!();
+
+    let mut tasks: PriorityQueue<u32> = (1..=10).collect();
+    tasks.retain(|task| task % 3 != 0);
+    println!("Retained tasks: {:?}", tasks.iter().count());
+    let urgent: Vec<u32> = tasks.drain_sorted().take(2).collect();
+    println!("Two most urgent: {urgent:?}, left: {}", tasks.len());
+
+    let mut numbers: PriorityQueue<u32, MinComparator> = PriorityQueue::from(vec![4, 1, 3]);
+    let drained: Vec<u32> = numbers.drain().collect();
+    println!(
+        "Drained {} numbers, queue is empty: {}",
+        drained.len(),
+        numbers.is_empty()
+    );
+    let unsorted = PriorityQueue::<u32>::from(drained.clone()).into_vec();
+    let sorted = PriorityQueue::<u32>::from(drained).into_sorted_vec();
+    println!("Heap layout {unsorted:?}, sorted {sorted:?}");
+
+    let shared = ConcurrentPriorityQueue::new();
+    thread::scope(|scope| {
+        for worker in 0..2 {
+            let shared = &shared;
+            scope.spawn(move || {
+                while let Some(task) = shared.pop() {
+                    println!("Worker {worker} took task {task}");
+                }
+            });
+        }
+
+        for task in [3, 9, 1, 7] {
+            if shared.push(task).is_err() {
+                println!("Task {task} rejected");
+            }
+        }
+        shared.close();
+    });
+    println!(
+        "Pool closed: {}, rejected push: {:?}, length: {}, drained: {}",
+        shared.is_closed(),
+        shared.push(5),
+        shared.len(),
+        shared.is_empty()
+    );
+    println!("Try pop on closed queue: {:?}", shared.try_pop());
+    match shared.pop_timeout(Duration::from_millis(10)) {
+        Ok(task) => println!("Late task {task}"),
+        Err(PopError::Closed) => println!("Queue closed: {}", PopError::Closed),
+        Err(err) => println!("Pop failed: {err}"),
+    }
+
+    let mut retries = DelayQueue::with_clock(ManualClock::new(Instant::now()));
+    retries.schedule_after("ping", Duration::from_secs(1));
+    let upload = retries.schedule_after("upload", Duration::from_secs(2));
+    let started = retries.clock().now();
+    retries.schedule_at("sync", started + Duration::from_secs(5));
+    println!("Cancelled {:?}", retries.cancel(upload));
+
+    retries.clock().advance(Duration::from_secs(3));
+    println!("Expired after 3s: {:?}", retries.poll());
+    println!(
+        "Pending {}, next deadline in {:?}",
+        retries.len(),
+        retries.next_deadline().map(|deadline| deadline - started)
+    );
+    println!(
+        "Expired at 10s: {:?}, is empty: {}",
+        retries.poll_expired(started + Duration::from_secs(10)),
+        retries.is_empty()
+    );
+
+    let mut real_timers = DelayQueue::new();
+    real_timers.schedule_after("now", Duration::ZERO);
+    println!("System clock timers: {:?}", real_timers.poll());
+
+    let mut window = RunningMedian::new();
+    let latencies = [12, 40, 7, 25, 31, 9];
+    for (index, &latency) in latencies.iter().enumerate() {
+        window.insert(latency);
+        if index >= 3 {
+            window.remove(&latencies[index - 3]);
+        }
+        println!("Window median after {latency}: {:?}", window.median());
+    }
+    println!(
+        "Window holds {} values, is empty: {}",
+        window.len(),
+        window.is_empty()
+    );
+
+    let mut p90 = RunningQuantile::new(0.9);
+    for latency in 1..=100 {
+        p90.insert(latency);
+    }
+    p90.remove(&100);
+    println!(
+        "90th percentile of {} values (is empty: {}): {:?}",
+        p90.len(),
+        p90.is_empty(),
+        p90.value()
+    );
+
+    let runs = vec![vec![1, 4, 9], vec![2, 4, 8], vec![], vec![3, 4, 10]];
+    let merged: Vec<i32> = kmerge(runs.clone()).collect();
+    println!("Merged runs: {merged:?}");
+    let unique: Vec<i32> = kmerge(runs).dedup().collect();
+    println!("Merged unique: {unique:?}");
+
+    println!(
+        "Binary heap: {:?}",
+        fill_and_drain(&mut PriorityQueue::new())
+    );
+    println!(
+        "Pairing heap: {:?}",
+        fill_and_drain(&mut PairingHeap::new())
+    );
+    println!(
+        "Leftist heap: {:?}",
+        fill_and_drain(&mut LeftistHeap::new())
+    );
+
+    let mut pairing = PairingHeap::new_min();
+    let mut other_pairing = PairingHeap::new_min();
+    pairing.push(4);
+    other_pairing.push(2);
+    pairing.meld(other_pairing);
+    println!(
+        "Melded pairing heap: length {}, min {:?}, is empty: {}",
+        pairing.len(),
+        pairing.pop(),
+        pairing.is_empty()
+    );
+
+    let mut leftist = LeftistHeap::new_min();
+    let mut other_leftist = LeftistHeap::new_min();
+    leftist.push(6);
+    other_leftist.push(1);
+    leftist.meld(other_leftist);
+    println!(
+        "Melded leftist heap: length {}, min {:?}, is empty: {}",
+        leftist.len(),
+        leftist.pop(),
+        leftist.is_empty()
+    );
+    println!("Leftist heap top after pop: {:?}", leftist.peek());
+
+    let mut frontier: RadixHeap<u32, char> = RadixHeap::new();
+    for (distance, vertex) in [(4, 'b'), (0, 'a'), (9, 'c')] {
+        if let Err(err) = frontier.push(distance, vertex) {
+            println!("{err}");
+        }
+    }
+    println!(
+        "Radix heap holds {} vertices, nearest is {:?}",
+        frontier.len(),
+        frontier.peek()
+    );
+    while let Some((distance, vertex)) = frontier.pop() {
+        println!("Settled {vertex} at {distance}");
+        if vertex == 'b'
+            && let Err(err) = frontier.push(2, 'd')
+        {
+            println!("Rejected: {err}");
+        }
+    }
+    println!("Radix heap is empty: {}", frontier.is_empty());
+
+    let mut spilling =
+        ExternalPriorityQueue::new(4, LineSerializer).with_directory(std::env::temp_dir());
+    for value in [15, 3, 42, 8, 23, 4, 16, 99, 1, 7] {
+        if let Err(err) = spilling.push(value) {
+            println!("Failed to spill: {err}");
+        }
+    }
+    println!(
+        "External queue holds {} values in {} runs, max is {:?}",
+        spilling.len(),
+        spilling.spilled_runs(),
+        spilling.peek()
+    );
+    print!("External queue order:");
+    while let Ok(Some(value)) = spilling.pop() {
+        print!(" {value}");
+    }
+    println!(", is empty: {}", spilling.is_empty());
+
+    let base = PersistentHeap::new().push(5).push(2).push(8);
+    let branch = base.push(10);
+    if let Some((top, rest)) = branch.pop() {
+        println!(
+            "Branch popped {top}, rest has {} values, base still tops at {:?}",
+            rest.len(),
+            base.peek()
+        );
+    }
+    let cheapest = PersistentHeap::new_min()
+        .push(3)
+        .meld(&PersistentHeap::new_min().push(1));
+    println!(
+        "Melded persistent heap: {:?} of {}, is empty: {}",
+        cheapest.peek(),
+        cheapest.len(),
+        cheapest.is_empty()
+    );
 }
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use crate::priority_queue::{Compare, MinComparator, PriorityQueue};
+
+// В lower лежат floor(q * (n - 1)) + 1 наименьших элементов, искомый квантиль - вершина lower.
+// Удалённые элементы остаются в кучах до тех пор, пока не окажутся на вершине
+pub struct RunningQuantile<T: Ord + Clone> {
+    quantile: f64,
+    lower: PriorityQueue<T>,
+    upper: PriorityQueue<T, MinComparator>,
+    lower_len: usize,
+    upper_len: usize,
+    lower_deleted: BTreeMap<T, usize>,
+    upper_deleted: BTreeMap<T, usize>,
+    counts: BTreeMap<T, usize>,
+}
+
+impl<T: Ord + Clone> RunningQuantile<T> {
+    pub fn new(quantile: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&quantile),
+            "quantile {quantile} is outside of [0, 1]"
+        );
+
+        RunningQuantile {
+            quantile,
+            lower: PriorityQueue::new(),
+            upper: PriorityQueue::new_min(),
+            lower_len: 0,
+            upper_len: 0,
+            lower_deleted: BTreeMap::new(),
+            upper_deleted: BTreeMap::new(),
+            counts: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.lower_len + self.upper_len
+    }
+
+    pub fn insert(&mut self, value: T) {
+        *self.counts.entry(value.clone()).or_insert(0) += 1;
+
+        if self.lower.peek().is_none_or(|top| value <= *top) {
+            self.lower.push(value);
+            self.lower_len += 1;
+        } else {
+            self.upper.push(value);
+            self.upper_len += 1;
+        }
+        self.rebalance();
+    }
+
+    pub fn remove(&mut self, value: &T) -> bool {
+        match self.counts.get_mut(value) {
+            None => return false,
+            Some(1) => {
+                self.counts.remove(value);
+            }
+            Some(count) => *count -= 1,
+        }
+
+        // Все элементы upper не меньше вершины lower, поэтому равный ей лежит в lower
+        if self.lower.peek().is_some_and(|top| value <= top) {
+            *self.lower_deleted.entry(value.clone()).or_insert(0) += 1;
+            self.lower_len -= 1;
+            Self::prune(&mut self.lower, &mut self.lower_deleted);
+        } else {
+            *self.upper_deleted.entry(value.clone()).or_insert(0) += 1;
+            self.upper_len -= 1;
+            Self::prune(&mut self.upper, &mut self.upper_deleted);
+        }
+        self.rebalance();
+        true
+    }
+
+    pub fn value(&self) -> Option<&T> {
+        self.lower.peek()
+    }
+
+    fn target_lower_len(&self) -> usize {
+        match self.len() {
+            0 => 0,
+            n => (self.quantile * (n - 1) as f64).floor() as usize + 1,
+        }
+    }
+
+    fn rebalance(&mut self) {
+        let target = self.target_lower_len();
+
+        while self.lower_len > target {
+            let value = self.lower.pop().unwrap();
+            Self::prune(&mut self.lower, &mut self.lower_deleted);
+            self.upper.push(value);
+            self.lower_len -= 1;
+            self.upper_len += 1;
+        }
+
+        while self.lower_len < target {
+            let value = self.upper.pop().unwrap();
+            Self::prune(&mut self.upper, &mut self.upper_deleted);
+            self.lower.push(value);
+            self.upper_len -= 1;
+            self.lower_len += 1;
+        }
+    }
+
+    fn prune<C: Compare<T>>(queue: &mut PriorityQueue<T, C>, deleted: &mut BTreeMap<T, usize>) {
+        while let Some(top) = queue.peek() {
+            match deleted.get_mut(top) {
+                None => break,
+                Some(1) => {
+                    deleted.remove(top);
+                }
+                Some(count) => *count -= 1,
+            }
+            queue.pop();
+        }
+    }
+}
+
+// Для чётного числа элементов возвращается нижняя медиана
+pub struct RunningMedian<T: Ord + Clone> {
+    inner: RunningQuantile<T>,
+}
+
+impl<T: Ord + Clone> RunningMedian<T> {
+    pub fn new() -> Self {
+        RunningMedian {
+            inner: RunningQuantile::new(0.5),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn insert(&mut self, value: T) {
+        self.inner.insert(value);
+    }
+
+    pub fn remove(&mut self, value: &T) -> bool {
+        self.inner.remove(value)
+    }
+
+    pub fn median(&self) -> Option<&T> {
+        self.inner.value()
+    }
+}
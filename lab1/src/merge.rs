@@ -0,0 +1,105 @@
+use std::cmp::Ordering;
+
+use crate::priority_queue::{MinComparator, PriorityQueue};
+
+// Равные элементы выдаются в порядке номеров источников
+struct Head<T> {
+    item: T,
+    source: usize,
+}
+
+impl<T: Ord> PartialEq for Head<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord> Eq for Head<T> {}
+
+impl<T: Ord> PartialOrd for Head<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Head<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.item
+            .cmp(&other.item)
+            .then(self.source.cmp(&other.source))
+    }
+}
+
+// Каждый источник должен быть отсортирован по возрастанию
+pub struct KMerge<I: Iterator> {
+    sources: Vec<I>,
+    heads: PriorityQueue<Head<I::Item>, MinComparator>,
+    dedup: bool,
+}
+
+impl<I> KMerge<I>
+where
+    I: Iterator,
+    I::Item: Ord,
+{
+    pub fn new<S>(sources: S) -> Self
+    where
+        S: IntoIterator,
+        S::Item: IntoIterator<IntoIter = I>,
+    {
+        let mut sources: Vec<I> = sources.into_iter().map(IntoIterator::into_iter).collect();
+        let heads = sources
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(source, iter)| iter.next().map(|item| Head { item, source }))
+            .collect();
+
+        KMerge {
+            sources,
+            heads,
+            dedup: false,
+        }
+    }
+
+    // Пропускать элементы, равные уже выданному
+    pub fn dedup(mut self) -> Self {
+        self.dedup = true;
+        self
+    }
+
+    fn next_item(&mut self) -> Option<I::Item> {
+        let Head { item, source } = self.heads.pop()?;
+        if let Some(next) = self.sources[source].next() {
+            self.heads.push(Head { item: next, source });
+        }
+        Some(item)
+    }
+}
+
+impl<I> Iterator for KMerge<I>
+where
+    I: Iterator,
+    I::Item: Ord,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.next_item()?;
+        if self.dedup {
+            // Источники отсортированы, поэтому все дубликаты сейчас на вершине кучи
+            while self.heads.peek().is_some_and(|head| head.item == item) {
+                self.next_item();
+            }
+        }
+        Some(item)
+    }
+}
+
+pub fn kmerge<S>(sources: S) -> KMerge<<S::Item as IntoIterator>::IntoIter>
+where
+    S: IntoIterator,
+    S::Item: IntoIterator,
+    <S::Item as IntoIterator>::Item: Ord,
+{
+    KMerge::new(sources)
+}
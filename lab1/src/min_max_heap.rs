@@ -0,0 +1,142 @@
+// Уровни с чётной глубиной (корень - глубина 0) хранят минимумы своих поддеревьев,
+// с нечётной - максимумы
+pub struct MinMaxHeap<T: Ord> {
+    heap: Vec<T>,
+}
+
+impl<T: Ord> MinMaxHeap<T> {
+    pub fn new() -> Self {
+        MinMaxHeap { heap: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.heap.push(value);
+        self.bubble_up(self.heap.len() - 1);
+    }
+
+    pub fn peek_min(&self) -> Option<&T> {
+        self.heap.first()
+    }
+
+    pub fn peek_max(&self) -> Option<&T> {
+        self.max_index().map(|index| &self.heap[index])
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        Some(self.remove_at(0))
+    }
+
+    pub fn pop_max(&mut self) -> Option<T> {
+        let index = self.max_index()?;
+        Some(self.remove_at(index))
+    }
+
+    fn max_index(&self) -> Option<usize> {
+        match self.heap.len() {
+            0 => None,
+            1 => Some(0),
+            2 => Some(1),
+            _ if self.heap[2] > self.heap[1] => Some(2),
+            _ => Some(1),
+        }
+    }
+
+    fn remove_at(&mut self, index: usize) -> T {
+        let result = self.heap.swap_remove(index);
+        if index < self.heap.len() {
+            self.trickle_down(index);
+        }
+        result
+    }
+
+    fn is_min_level(index: usize) -> bool {
+        (index + 1).ilog2().is_multiple_of(2)
+    }
+
+    fn parent(index: usize) -> usize {
+        (index - 1) / 2
+    }
+
+    fn better(&self, a: usize, b: usize, min: bool) -> bool {
+        if min {
+            self.heap[a] < self.heap[b]
+        } else {
+            self.heap[a] > self.heap[b]
+        }
+    }
+
+    fn bubble_up(&mut self, index: usize) {
+        if index == 0 {
+            return;
+        }
+
+        let min = Self::is_min_level(index);
+        let parent = Self::parent(index);
+        if self.better(index, parent, !min) {
+            // Элемент принадлежит уровням противоположного типа
+            self.heap.swap(index, parent);
+            self.bubble_up_grandparents(parent, !min);
+        } else {
+            self.bubble_up_grandparents(index, min);
+        }
+    }
+
+    fn bubble_up_grandparents(&mut self, mut index: usize, min: bool) {
+        while index >= 3 {
+            let grandparent = Self::parent(Self::parent(index));
+            if !self.better(index, grandparent, min) {
+                break;
+            }
+            self.heap.swap(index, grandparent);
+            index = grandparent;
+        }
+    }
+
+    fn trickle_down(&mut self, mut index: usize) {
+        let min = Self::is_min_level(index);
+        let len = self.heap.len();
+
+        loop {
+            let first_child = 2 * index + 1;
+            if first_child >= len {
+                break;
+            }
+
+            // Лучший среди детей и внуков
+            let first_grandchild = 2 * first_child + 1;
+            let candidates = (first_child..(first_child + 2).min(len))
+                .chain(first_grandchild..(first_grandchild + 4).min(len));
+            let mut best = first_child;
+            for candidate in candidates {
+                if self.better(candidate, best, min) {
+                    best = candidate;
+                }
+            }
+
+            if !self.better(best, index, min) {
+                break;
+            }
+            self.heap.swap(best, index);
+
+            if best < first_grandchild {
+                break;
+            }
+            let parent = Self::parent(best);
+            if self.better(parent, best, min) {
+                self.heap.swap(best, parent);
+            }
+            index = best;
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::{cmp::Ordering, mem};
+
+use crate::{
+    heap::Heap,
+    priority_queue::{Compare, MaxComparator, MinComparator},
+};
+
+struct Node<T> {
+    value: T,
+    children: Vec<Box<Node<T>>>,
+}
+
+pub struct PairingHeap<T, C = MaxComparator> {
+    root: Option<Box<Node<T>>>,
+    len: usize,
+    comparator: C,
+}
+
+impl<T: Ord> PairingHeap<T> {
+    pub fn new() -> Self {
+        PairingHeap::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> PairingHeap<T, MinComparator> {
+    pub fn new_min() -> Self {
+        PairingHeap::with_comparator(MinComparator)
+    }
+}
+
+impl<T, C: Compare<T>> PairingHeap<T, C> {
+    pub fn with_comparator(comparator: C) -> Self {
+        PairingHeap {
+            root: None,
+            len: 0,
+            comparator,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn push(&mut self, value: T) {
+        let node = Box::new(Node {
+            value,
+            children: Vec::new(),
+        });
+        let root = self.root.take();
+        self.root = Some(self.link(root, node));
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let root = self.root.take()?;
+        let Node { value, children } = *root;
+        self.root = self.merge_pairs(children);
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.value)
+    }
+
+    // O(1): корень с меньшим приоритетом становится потомком другого
+    pub fn meld(&mut self, mut other: Self) {
+        if let Some(node) = other.root.take() {
+            let root = self.root.take();
+            self.root = Some(self.link(root, node));
+            self.len += mem::take(&mut other.len);
+        }
+    }
+
+    fn link(&self, a: Option<Box<Node<T>>>, b: Box<Node<T>>) -> Box<Node<T>> {
+        let Some(a) = a else {
+            return b;
+        };
+
+        let (mut parent, child) = match self.comparator.compare(&a.value, &b.value) {
+            Ordering::Less => (b, a),
+            _ => (a, b),
+        };
+        parent.children.push(child);
+        parent
+    }
+
+    // Двухпроходное слияние: попарно слева направо, затем справа налево
+    fn merge_pairs(&self, children: Vec<Box<Node<T>>>) -> Option<Box<Node<T>>> {
+        let mut pairs = Vec::with_capacity(children.len().div_ceil(2));
+        let mut children = children.into_iter();
+        while let Some(first) = children.next() {
+            pairs.push(match children.next() {
+                Some(second) => self.link(Some(first), second),
+                None => first,
+            });
+        }
+
+        pairs
+            .into_iter()
+            .rev()
+            .fold(None, |acc, node| Some(self.link(acc, node)))
+    }
+}
+
+// Дерево может выродиться в длинную цепочку, поэтому освобождаем его без рекурсии
+impl<T, C> Drop for PairingHeap<T, C> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.append(&mut node.children);
+        }
+    }
+}
+
+impl<T, C: Compare<T>> Heap<T> for PairingHeap<T, C> {
+    fn push(&mut self, value: T) {
+        PairingHeap::push(self, value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        PairingHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        PairingHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        PairingHeap::len(self)
+    }
+}
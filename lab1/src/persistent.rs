@@ -0,0 +1,136 @@
+use std::{cmp::Ordering, rc::Rc};
+
+use crate::priority_queue::{Compare, MaxComparator, MinComparator};
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+struct Node<T> {
+    value: Rc<T>,
+    rank: usize,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+// Левосторонняя куча с копированием пути: каждая операция создаёт O(log n) новых узлов,
+// остальная часть дерева разделяется между версиями
+pub struct PersistentHeap<T, C = MaxComparator> {
+    root: Link<T>,
+    len: usize,
+    comparator: Rc<C>,
+}
+
+impl<T: Ord> PersistentHeap<T> {
+    pub fn new() -> Self {
+        PersistentHeap::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> PersistentHeap<T, MinComparator> {
+    pub fn new_min() -> Self {
+        PersistentHeap::with_comparator(MinComparator)
+    }
+}
+
+impl<T, C: Compare<T>> PersistentHeap<T, C> {
+    pub fn with_comparator(comparator: C) -> Self {
+        PersistentHeap {
+            root: None,
+            len: 0,
+            comparator: Rc::new(comparator),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &*node.value)
+    }
+
+    pub fn push(&self, value: T) -> Self {
+        let node = Rc::new(Node {
+            value: Rc::new(value),
+            rank: 1,
+            left: None,
+            right: None,
+        });
+        self.version(self.merge(&self.root, &Some(node)), self.len + 1)
+    }
+
+    // Возвращает вершину и версию кучи без неё
+    pub fn pop(&self) -> Option<(&T, Self)> {
+        let root = self.root.as_ref()?;
+        let rest = self.version(self.merge(&root.left, &root.right), self.len - 1);
+        Some((&*root.value, rest))
+    }
+
+    pub fn meld(&self, other: &Self) -> Self {
+        self.version(self.merge(&self.root, &other.root), self.len + other.len)
+    }
+
+    fn version(&self, root: Link<T>, len: usize) -> Self {
+        PersistentHeap {
+            root,
+            len,
+            comparator: Rc::clone(&self.comparator),
+        }
+    }
+
+    fn rank(node: &Link<T>) -> usize {
+        node.as_ref().map_or(0, |n| n.rank)
+    }
+
+    fn merge(&self, a: &Link<T>, b: &Link<T>) -> Link<T> {
+        let (a, b) = match (a, b) {
+            (None, b) => return b.clone(),
+            (a, None) => return a.clone(),
+            (Some(a), Some(b)) => (a, b),
+        };
+
+        let (parent, child) = match self.comparator.compare(&a.value, &b.value) {
+            Ordering::Less => (b, a),
+            _ => (a, b),
+        };
+
+        let mut left = parent.left.clone();
+        let mut right = self.merge(&parent.right, &Some(Rc::clone(child)));
+        if Self::rank(&left) < Self::rank(&right) {
+            std::mem::swap(&mut left, &mut right);
+        }
+
+        Some(Rc::new(Node {
+            value: Rc::clone(&parent.value),
+            rank: Self::rank(&right) + 1,
+            left,
+            right,
+        }))
+    }
+}
+
+impl<T, C> Clone for PersistentHeap<T, C> {
+    fn clone(&self) -> Self {
+        PersistentHeap {
+            root: self.root.clone(),
+            len: self.len,
+            comparator: Rc::clone(&self.comparator),
+        }
+    }
+}
+
+// Освобождаем только узлы, которые больше ни одной версией не используются, без рекурсии
+impl<T, C> Drop for PersistentHeap<T, C> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Rc<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            if let Ok(mut node) = Rc::try_unwrap(node) {
+                stack.extend(node.left.take());
+                stack.extend(node.right.take());
+            }
+        }
+    }
+}
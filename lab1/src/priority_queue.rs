@@ -1,10 +1,146 @@
-pub struct PriorityQueue<T: Ord> {
-    heap: Vec<T>,
+use std::{
+    cmp::{self, Ordering},
+    ops::{Deref, DerefMut},
+};
+
+// Greater означает более высокий приоритет (ближе к вершине кучи)
+pub trait Compare<T> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MaxComparator;
+
+impl<T: Ord> Compare<T> for MaxComparator {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MinComparator;
+
+impl<T: Ord> Compare<T> for MinComparator {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FnComparator<F>(pub F);
+
+impl<T, F> Compare<T> for FnComparator<F>
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a, b)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct KeyComparator<F>(pub F);
+
+impl<T, K, F> Compare<T> for KeyComparator<F>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a).cmp(&(self.0)(b))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Reversed<C>(pub C);
+
+impl<T, C: Compare<T>> Compare<T> for Reversed<C> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self.0.compare(b, a)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    slot: usize,
+    generation: usize,
+}
+
+struct Slot {
+    position: Option<usize>,
+    generation: usize,
+}
+
+struct Entry<T> {
+    value: T,
+    slot: usize,
+    sequence: u64,
+}
+
+// D - арность кучи, у узла i потомки D * i + 1 ..= D * i + D
+pub struct PriorityQueue<T, C = MaxComparator, const D: usize = 2> {
+    heap: Vec<Entry<T>>,
+    slots: Vec<Slot>,
+    free: Vec<usize>,
+    comparator: C,
+    stable: bool,
+    next_sequence: u64,
 }
 
 impl<T: Ord> PriorityQueue<T> {
     pub fn new() -> Self {
-        PriorityQueue { heap: Vec::new() }
+        PriorityQueue::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> PriorityQueue<T, MinComparator> {
+    pub fn new_min() -> Self {
+        PriorityQueue::with_comparator(MinComparator)
+    }
+}
+
+impl<T, F> PriorityQueue<T, FnComparator<F>>
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    pub fn new_by(compare: F) -> Self {
+        PriorityQueue::with_comparator(FnComparator(compare))
+    }
+}
+
+impl<T, K, F> PriorityQueue<T, KeyComparator<F>>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    pub fn new_by_key(key: F) -> Self {
+        PriorityQueue::with_comparator(KeyComparator(key))
+    }
+}
+
+impl<T, C: Compare<T>, const D: usize> PriorityQueue<T, C, D> {
+    pub fn with_comparator(comparator: C) -> Self {
+        const { assert!(D >= 2, "heap arity must be at least 2") };
+
+        PriorityQueue {
+            heap: Vec::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            comparator,
+            stable: false,
+            next_sequence: 0,
+        }
+    }
+
+    // Равные по приоритету элементы извлекаются в порядке добавления
+    pub fn stable(mut self) -> Self {
+        self.stable = true;
+        self.heapify();
+        self
+    }
+
+    pub fn comparator(&self) -> &C {
+        &self.comparator
     }
 
     pub fn is_empty(&self) -> bool {
@@ -15,59 +151,374 @@ impl<T: Ord> PriorityQueue<T> {
         self.heap.len()
     }
 
-    pub fn push(&mut self, value: T) {
-        self.heap.push(value);
-        self.sift_up();
+    pub fn push(&mut self, value: T) -> Handle {
+        let handle = self.push_entry(value);
+        self.sift_up(self.heap.len() - 1);
+        handle
     }
 
     pub fn pop(&mut self) -> Option<T> {
         if self.heap.is_empty() {
             return None;
         }
+        Some(self.remove_at(0))
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.first().map(|entry| &entry.value)
+    }
+
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, C, D>> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        Some(PeekMut {
+            queue: self,
+            changed: false,
+        })
+    }
+
+    // Дескриптор вершины остаётся действительным и указывает на новое значение.
+    // В стабильном режиме элемент, изменённый через replace_top, peek_mut или
+    // change_priority, встаёт после равных ему, как если бы его добавили заново
+    pub fn replace_top(&mut self, value: T) -> Option<T> {
+        if self.heap.is_empty() {
+            self.push(value);
+            return None;
+        }
+
+        let old = std::mem::replace(&mut self.heap[0].value, value);
+        self.heap[0].sequence = self.next_sequence();
+        self.sift_down(0);
+        Some(old)
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.position(handle).is_some()
+    }
+
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let position = self.position(handle)?;
+        Some(self.remove_at(position))
+    }
+
+    // Возвращает старое значение, либо новое обратно, если элемент уже удалён
+    pub fn change_priority(&mut self, handle: Handle, value: T) -> Result<T, T> {
+        let Some(position) = self.position(handle) else {
+            return Err(value);
+        };
+
+        let old = std::mem::replace(&mut self.heap[position].value, value);
+        self.heap[position].sequence = self.next_sequence();
+        let position = self.sift_up(position);
+        self.sift_down(position);
+
+        Ok(old)
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        let start = self.heap.len();
+        for value in other.take_values() {
+            self.push_entry(value);
+        }
+        self.rebuild_tail(start);
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            entries: self.heap.iter(),
+        }
+    }
+
+    // Произвольный порядок, все дескрипторы становятся недействительными
+    pub fn drain(&mut self) -> std::vec::IntoIter<T> {
+        self.take_values().into_iter()
+    }
+
+    pub fn drain_sorted(&mut self) -> DrainSorted<'_, T, C, D> {
+        DrainSorted { queue: self }
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.heap.into_iter().map(|entry| entry.value).collect()
+    }
+
+    // Пирамидальная сортировка на месте, вершина кучи оказывается в конце
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        for end in (1..self.heap.len()).rev() {
+            self.swap(0, end);
+            self.sift_down_to(0, end);
+        }
+        self.into_vec()
+    }
+
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut predicate: F) {
+        let entries = std::mem::take(&mut self.heap);
+        let len = entries.len();
 
+        for entry in entries {
+            if predicate(&entry.value) {
+                self.slots[entry.slot].position = Some(self.heap.len());
+                self.heap.push(entry);
+            } else {
+                self.release(entry.slot);
+            }
+        }
+
+        if self.heap.len() < len {
+            self.heapify();
+        }
+    }
+
+    fn push_entry(&mut self, value: T) -> Handle {
+        let position = self.heap.len();
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot].position = Some(position);
+                slot
+            }
+            None => {
+                self.slots.push(Slot {
+                    position: Some(position),
+                    generation: 0,
+                });
+                self.slots.len() - 1
+            }
+        };
+
+        let sequence = self.next_sequence();
+        self.heap.push(Entry {
+            value,
+            slot,
+            sequence,
+        });
+
+        Handle {
+            slot,
+            generation: self.slots[slot].generation,
+        }
+    }
+
+    fn release(&mut self, slot: usize) {
+        let entry = &mut self.slots[slot];
+        entry.position = None;
+        entry.generation += 1;
+        self.free.push(slot);
+    }
+
+    fn take_values(&mut self) -> Vec<T> {
+        let mut entries = std::mem::take(&mut self.heap);
+        if self.stable {
+            entries.sort_unstable_by_key(|entry| entry.sequence);
+        }
+        entries
+            .into_iter()
+            .map(|entry| {
+                self.release(entry.slot);
+                entry.value
+            })
+            .collect()
+    }
+
+    // Досеивание хвоста [start..len) стоит tail * log(start) сравнений,
+    // полная перестройка кучи снизу вверх - порядка 2 * len
+    fn rebuild_tail(&mut self, start: usize) {
+        let len = self.heap.len();
+        let tail = len - start;
+        if tail == 0 {
+            return;
+        }
+
+        let rebuild = start < tail || 2 * len < tail * start.ilog(D) as usize;
+        if rebuild {
+            self.heapify();
+        } else {
+            for position in start..len {
+                self.sift_up(position);
+            }
+        }
+    }
+
+    fn heapify(&mut self) {
+        if self.heap.len() < 2 {
+            return;
+        }
+        for position in (0..=(self.heap.len() - 2) / D).rev() {
+            self.sift_down(position);
+        }
+    }
+
+    fn position(&self, handle: Handle) -> Option<usize> {
+        let slot = self.slots.get(handle.slot)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        slot.position
+    }
+
+    fn remove_at(&mut self, position: usize) -> T {
         let last = self.heap.len() - 1;
-        self.heap.swap(0, last);
+        self.swap(position, last);
 
-        let result = self.heap.pop();
-        self.sift_down();
+        let entry = self.heap.pop().unwrap();
+        self.release(entry.slot);
 
-        result
+        if position < self.heap.len() {
+            let position = self.sift_up(position);
+            self.sift_down(position);
+        }
+
+        entry.value
     }
 
-    pub fn peek(&self) -> Option<&T> {
-        self.heap.first()
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.slots[self.heap[a].slot].position = Some(a);
+        self.slots[self.heap[b].slot].position = Some(b);
+    }
+
+    fn higher(&self, a: usize, b: usize) -> bool {
+        let (a, b) = (&self.heap[a], &self.heap[b]);
+        match self.comparator.compare(&a.value, &b.value) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            // В стабильном режиме из равных первым выходит добавленный раньше
+            Ordering::Equal => self.stable && a.sequence < b.sequence,
+        }
     }
 
-    fn sift_up(&mut self) {
-        let mut current = self.heap.len() - 1;
+    fn next_sequence(&mut self) -> u64 {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        sequence
+    }
+
+    fn sift_up(&mut self, mut current: usize) -> usize {
         while current > 0 {
-            let parent = (current - 1) / 2;
-            if self.heap[current] <= self.heap[parent] {
+            let parent = (current - 1) / D;
+            if !self.higher(current, parent) {
                 break;
             }
-            self.heap.swap(current, parent);
+            self.swap(current, parent);
             current = parent;
         }
+        current
     }
 
-    fn sift_down(&mut self) {
-        let mut current = 0;
+    fn sift_down(&mut self, current: usize) -> usize {
+        self.sift_down_to(current, self.heap.len())
+    }
+
+    fn sift_down_to(&mut self, mut current: usize, end: usize) -> usize {
         loop {
-            let left = 2 * current + 1;
-            let right = 2 * current + 2;
+            let first = D * current + 1;
+            let last = cmp::min(first + D, end);
             let mut largest = current;
 
-            if left < self.heap.len() && self.heap[left] > self.heap[largest] {
-                largest = left;
-            }
-            if right < self.heap.len() && self.heap[right] > self.heap[largest] {
-                largest = right;
+            for child in first..last {
+                if self.higher(child, largest) {
+                    largest = child;
+                }
             }
             if largest == current {
                 break;
             }
-            self.heap.swap(current, largest);
+            self.swap(current, largest);
             current = largest;
         }
+        current
+    }
+}
+
+pub struct Iter<'a, T> {
+    entries: std::slice::Iter<'a, Entry<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|entry| &entry.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+pub struct DrainSorted<'a, T, C: Compare<T>, const D: usize> {
+    queue: &'a mut PriorityQueue<T, C, D>,
+}
+
+impl<T, C: Compare<T>, const D: usize> Iterator for DrainSorted<'_, T, C, D> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.queue.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.queue.len(), Some(self.queue.len()))
+    }
+}
+
+impl<T, C: Compare<T>, const D: usize> Drop for DrainSorted<'_, T, C, D> {
+    fn drop(&mut self) {
+        self.queue.take_values();
+    }
+}
+
+pub struct PeekMut<'a, T, C: Compare<T>, const D: usize> {
+    queue: &'a mut PriorityQueue<T, C, D>,
+    changed: bool,
+}
+
+impl<T, C: Compare<T>, const D: usize> Deref for PeekMut<'_, T, C, D> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.queue.heap[0].value
+    }
+}
+
+impl<T, C: Compare<T>, const D: usize> DerefMut for PeekMut<'_, T, C, D> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.changed = true;
+        &mut self.queue.heap[0].value
+    }
+}
+
+impl<T, C: Compare<T>, const D: usize> Drop for PeekMut<'_, T, C, D> {
+    fn drop(&mut self) {
+        if self.changed {
+            self.queue.heap[0].sequence = self.queue.next_sequence();
+            self.queue.sift_down(0);
+        }
+    }
+}
+
+impl<T, C: Compare<T> + Default, const D: usize> From<Vec<T>> for PriorityQueue<T, C, D> {
+    fn from(values: Vec<T>) -> Self {
+        let mut queue = PriorityQueue::with_comparator(C::default());
+        queue.extend(values);
+        queue
+    }
+}
+
+impl<T, C: Compare<T> + Default, const D: usize> FromIterator<T> for PriorityQueue<T, C, D> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut queue = PriorityQueue::with_comparator(C::default());
+        queue.extend(iter);
+        queue
+    }
+}
+
+impl<T, C: Compare<T>, const D: usize> Extend<T> for PriorityQueue<T, C, D> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let start = self.heap.len();
+        for value in iter {
+            self.push_entry(value);
+        }
+        self.rebuild_tail(start);
     }
 }
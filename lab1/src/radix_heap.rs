@@ -0,0 +1,113 @@
+use std::{error::Error, fmt};
+
+pub trait RadixKey: Copy + Ord {
+    const BITS: u32;
+    const MIN: Self;
+
+    // Номер старшего различающегося бита плюс один, 0 для равных ключей
+    fn bucket(self, last: Self) -> usize;
+}
+
+macro_rules! impl_radix_key {
+    ($($t:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                const BITS: u32 = <$t>::BITS;
+                const MIN: Self = 0;
+
+                fn bucket(self, last: Self) -> usize {
+                    (Self::BITS - (self ^ last).leading_zeros()) as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_key!(u8, u16, u32, u64, u128, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonMonotoneKey<K, V> {
+    pub key: K,
+    pub value: V,
+    pub last: K,
+}
+
+impl<K: fmt::Display, V> fmt::Display for NonMonotoneKey<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "key {} is less than the last extracted key {}",
+            self.key, self.last
+        )
+    }
+}
+
+impl<K: fmt::Debug + fmt::Display, V: fmt::Debug> Error for NonMonotoneKey<K, V> {}
+
+// Мин-куча для монотонных ключей: новый ключ не может быть меньше последнего извлечённого.
+// В корзине i лежат ключи, у которых старший отличающийся от last бит имеет номер i - 1
+pub struct RadixHeap<K: RadixKey, V> {
+    buckets: Vec<Vec<(K, V)>>,
+    last: K,
+    len: usize,
+}
+
+impl<K: RadixKey, V> RadixHeap<K, V> {
+    pub fn new() -> Self {
+        RadixHeap {
+            buckets: (0..=K::BITS).map(|_| Vec::new()).collect(),
+            last: K::MIN,
+            len: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn push(&mut self, key: K, value: V) -> Result<(), NonMonotoneKey<K, V>> {
+        if key < self.last {
+            return Err(NonMonotoneKey {
+                key,
+                value,
+                last: self.last,
+            });
+        }
+
+        self.buckets[key.bucket(self.last)].push((key, value));
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        if self.buckets[0].is_empty() {
+            self.redistribute()?;
+        }
+        self.len -= 1;
+        self.buckets[0].pop()
+    }
+
+    // O(1), если минимум уже в нулевой корзине, иначе просмотр одной корзины
+    pub fn peek(&self) -> Option<(K, &V)> {
+        let bucket = self.buckets.iter().find(|bucket| !bucket.is_empty())?;
+        bucket
+            .iter()
+            .min_by_key(|(key, _)| *key)
+            .map(|(key, value)| (*key, value))
+    }
+
+    fn redistribute(&mut self) -> Option<()> {
+        let index = self.buckets.iter().position(|bucket| !bucket.is_empty())?;
+        let items = std::mem::take(&mut self.buckets[index]);
+
+        self.last = items.iter().map(|(key, _)| *key).min()?;
+        for (key, value) in items {
+            self.buckets[key.bucket(self.last)].push((key, value));
+        }
+        Some(())
+    }
+}
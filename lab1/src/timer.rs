@@ -0,0 +1,135 @@
+use std::{
+    cell::Cell,
+    cmp::Ordering,
+    time::{Duration, Instant},
+};
+
+use crate::priority_queue::{Handle, MinComparator, PriorityQueue};
+
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+// Время двигается только вручную, что делает поведение очереди детерминированным
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Cell<Instant>,
+}
+
+impl ManualClock {
+    pub fn new(start: Instant) -> Self {
+        ManualClock {
+            now: Cell::new(start),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
+
+struct Timer<T> {
+    deadline: Instant,
+    item: T,
+}
+
+impl<T> PartialEq for Timer<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+
+impl<T> Eq for Timer<T> {}
+
+impl<T> PartialOrd for Timer<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Timer<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.deadline.cmp(&other.deadline)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerHandle(Handle);
+
+// Элементы с одинаковым сроком выдаются в порядке добавления
+pub struct DelayQueue<T, K = SystemClock> {
+    clock: K,
+    timers: PriorityQueue<Timer<T>, MinComparator>,
+}
+
+impl<T> DelayQueue<T> {
+    pub fn new() -> Self {
+        DelayQueue::with_clock(SystemClock)
+    }
+}
+
+impl<T, K: Clock> DelayQueue<T, K> {
+    pub fn with_clock(clock: K) -> Self {
+        DelayQueue {
+            clock,
+            timers: PriorityQueue::new_min().stable(),
+        }
+    }
+
+    pub fn clock(&self) -> &K {
+        &self.clock
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    pub fn schedule_at(&mut self, item: T, deadline: Instant) -> TimerHandle {
+        TimerHandle(self.timers.push(Timer { deadline, item }))
+    }
+
+    pub fn schedule_after(&mut self, item: T, delay: Duration) -> TimerHandle {
+        let deadline = self.clock.now() + delay;
+        self.schedule_at(item, deadline)
+    }
+
+    pub fn cancel(&mut self, handle: TimerHandle) -> Option<T> {
+        self.timers.remove(handle.0).map(|timer| timer.item)
+    }
+
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.timers.peek().map(|timer| timer.deadline)
+    }
+
+    pub fn poll_expired(&mut self, now: Instant) -> Vec<T> {
+        let mut expired = Vec::new();
+        while self.next_deadline().is_some_and(|deadline| deadline <= now) {
+            expired.extend(self.timers.pop().map(|timer| timer.item));
+        }
+        expired
+    }
+
+    pub fn poll(&mut self) -> Vec<T> {
+        let now = self.clock.now();
+        self.poll_expired(now)
+    }
+}
@@ -0,0 +1,76 @@
+use std::cmp::Ordering;
+
+use crate::priority_queue::{Compare, MaxComparator, MinComparator, PriorityQueue, Reversed};
+
+// На вершине внутренней кучи лежит худший из сохранённых элементов
+pub struct TopK<T, C = MaxComparator> {
+    capacity: usize,
+    queue: PriorityQueue<T, Reversed<C>>,
+}
+
+impl<T: Ord> TopK<T> {
+    pub fn largest(capacity: usize) -> Self {
+        TopK::with_comparator(capacity, MaxComparator)
+    }
+}
+
+impl<T: Ord> TopK<T, MinComparator> {
+    pub fn smallest(capacity: usize) -> Self {
+        TopK::with_comparator(capacity, MinComparator)
+    }
+}
+
+impl<T, C: Compare<T>> TopK<T, C> {
+    pub fn with_comparator(capacity: usize, comparator: C) -> Self {
+        TopK {
+            capacity,
+            queue: PriorityQueue::with_comparator(Reversed(comparator)),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    // Возвращает вытесненный элемент или сам value, если он не попал в K лучших
+    pub fn push(&mut self, value: T) -> Option<T> {
+        if self.queue.len() < self.capacity {
+            self.queue.push(value);
+            return None;
+        }
+
+        let comparator = &self.queue.comparator().0;
+        match self.queue.peek() {
+            Some(worst) if comparator.compare(&value, worst) == Ordering::Greater => {
+                self.queue.replace_top(value)
+            }
+            _ => Some(value),
+        }
+    }
+
+    // Лучший элемент идёт первым
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut result = Vec::with_capacity(self.queue.len());
+        while let Some(value) = self.queue.pop() {
+            result.push(value);
+        }
+        result.reverse();
+        result
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for TopK<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
@@ -1,6 +1,8 @@
 mod map;
+mod set;
 
-use map::Map;
+use map::{Entry, Map};
+use set::Set;
 
 const KEY: i32 = 6;
 
@@ -42,7 +44,156 @@ fn main() {
         println!("Key: {}, Value: {}", key, value);
     }
 
-    b.remove(&KEY);
+    if let Some(value) = b.remove(&KEY) {
+        println!("Removed key {} with value {}", KEY, value);
+    }
+    if let Some((key, value)) = b.remove_entry(&3) {
+        println!("Removed entry {}: {}", key, value);
+    }
+
+    let mut lengths: Map<i32, usize> = Map::new();
+    for (key, value) in &b {
+        lengths.insert(*key, value.len());
+    }
+    for (_, length) in lengths.iter_mut() {
+        *length *= 2;
+    }
+    for (key, length) in &mut lengths {
+        *length += 1;
+        println!("Key: {}, Doubled length plus one: {}", key, length);
+    }
+
+    let mut counts: Map<&str, usize> = Map::new();
+    for word in "the quick fox jumps over the lazy dog the end".split(' ') {
+        counts
+            .entry(word)
+            .and_modify(|count| *count += 1)
+            .or_insert(1);
+    }
+    *counts.entry("fox").or_insert_with(|| 0) += 10;
+    if let Some(count) = counts.get_mut(&"dog") {
+        *count += 100;
+    }
+    println!(
+        "Count of \"the\": {:?}, has \"cat\": {}, entry for \"dog\": {:?}",
+        counts.get(&"the"),
+        counts.contains_key(&"cat"),
+        counts.get_key_value(&"dog")
+    );
+
+    for word in ["fox", "cat"] {
+        match counts.entry(word) {
+            Entry::Occupied(mut entry) => {
+                let old = entry.insert(0);
+                println!("Reset {} from {} to {}", entry.key(), old, entry.get());
+            }
+            Entry::Vacant(entry) => {
+                println!("Adding missing {}", entry.key());
+                entry.insert(1);
+            }
+        }
+    }
+    println!("Entry key lookup: {}", counts.entry("end").key());
+
+    println!("Keys from 2 to 9 backwards:");
+    for (key, value) in b.range(2..=9).rev() {
+        println!("Key: {}, Value: {}", key, value);
+    }
+    for (_, value) in b.range_mut(..0) {
+        *value = "negative";
+    }
+    println!("Value of key -2 after range_mut: {:?}", b.get(&-2));
+
+    println!(
+        "Size: {}, 3rd smallest: {:?}, rank of 9: {}, keys in 2..=9: {}",
+        b.len(),
+        b.nth(2),
+        b.rank(&9),
+        b.count_range(2..=9)
+    );
+
+    println!(
+        "Floor of {}: {:?}, ceiling: {:?}, predecessor: {:?}, successor: {:?}",
+        KEY,
+        b.floor(&KEY),
+        b.ceiling(&KEY),
+        b.predecessor(&7),
+        b.successor(&7)
+    );
+    println!(
+        "Keys from 8: {:?}, keys after 8: {:?}",
+        b.lower_bound(&8).map(|(key, _)| *key).collect::<Vec<_>>(),
+        b.upper_bound(&8).map(|(key, _)| *key).collect::<Vec<_>>()
+    );
+    println!(
+        "First: {:?}, last: {:?}",
+        b.first_key_value(),
+        b.last_key_value()
+    );
+    let mut c = b.clone();
+    println!(
+        "Popped first: {:?}, popped last: {:?}, left: {}",
+        c.pop_first(),
+        c.pop_last(),
+        c.len()
+    );
+
+    let mut upper = c.split_off(&8);
+    println!(
+        "Split at 8: lower {:?}, upper {:?}",
+        c.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+        upper.iter().map(|(key, _)| *key).collect::<Vec<_>>()
+    );
+    let mut overlap = Map::new();
+    overlap.insert(4, "new four");
+    overlap.insert(11, "eleven");
+    upper.append(&mut overlap);
+    c.append(&mut upper);
+    println!(
+        "After append: {} keys, value of 4: {:?}",
+        c.len(),
+        c.get(&4)
+    );
+    let high = c.split_off(&9);
+    let joined = Map::join(c, 9, "nine", high);
+    for (key, value) in &joined {
+        println!("Joined key: {}, Value: {}", key, value);
+    }
+
+    let mut evens = Set::new();
+    let mut small = Set::new();
+    for number in 0..10 {
+        evens.insert(number * 2);
+        small.insert(number);
+    }
+    println!(
+        "Inserted 4 again: {}, removed 3: {}, contains 3: {}, size: {}",
+        evens.insert(4),
+        small.remove(&3),
+        small.contains(&3),
+        small.len()
+    );
+    println!(
+        "Evens in 5..12: {:?}, first: {:?}, last: {:?}",
+        evens.range(5..12).collect::<Vec<_>>(),
+        evens.first(),
+        evens.last()
+    );
+    for (name, result) in [
+        ("Union", evens.clone().union(small.clone())),
+        ("Intersection", evens.clone().intersection(small.clone())),
+        ("Difference", evens.clone().difference(small.clone())),
+        ("Symmetric difference", evens.symmetric_difference(small)),
+    ] {
+        println!("{}: {:?}", name, result.iter().rev().collect::<Vec<_>>());
+    }
+    let mut empty: Set<i32> = Set::new();
+    empty.insert(1);
+    empty.clear();
+    for key in &empty {
+        println!("Unexpected key {}", key);
+    }
+    println!("Cleared set is empty: {}", empty.is_empty());
 
     println!("Full BST:");
     for (key, value) in b {
@@ -1,23 +1,48 @@
-use std::{cmp, ops::Index};
+use std::{
+    cmp,
+    collections::VecDeque,
+    ops::{Bound, Index, RangeBounds},
+};
 
 #[derive(Debug, Clone)]
-struct Node<K: Ord, V: Clone> {
+struct Node<K: Ord, V> {
     key: K,
     value: V,
     left: Option<Box<Node<K, V>>>,
     right: Option<Box<Node<K, V>>>,
     height: isize,
+    size: usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    fn opposite(self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+// Путь от корня до узла: в какую сторону спускаться на каждом шаге
+type Path = Vec<Side>;
+
+// Поддерево, которое может быть пустым
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
 #[derive(Debug, Clone)]
-pub struct Map<K: Ord, V: Clone> {
+pub struct Map<K: Ord, V> {
     root: Option<Box<Node<K, V>>>,
 }
 
 impl<K, V> Map<K, V>
 where
-    K: Ord + Clone,
-    V: Clone,
+    K: Ord,
 {
     pub fn new() -> Self {
         Map { root: None }
@@ -27,6 +52,60 @@ where
         self.root.is_none()
     }
 
+    pub fn len(&self) -> usize {
+        Self::size(&self.root)
+    }
+
+    // k-й по возрастанию ключ, начиная с нуля
+    pub fn nth(&self, mut k: usize) -> Option<(&K, &V)> {
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
+            let left = Self::size(&node.left);
+            match k.cmp(&left) {
+                cmp::Ordering::Less => current = node.left.as_deref(),
+                cmp::Ordering::Equal => return Some((&node.key, &node.value)),
+                cmp::Ordering::Greater => {
+                    k -= left + 1;
+                    current = node.right.as_deref();
+                }
+            }
+        }
+        None
+    }
+
+    // Количество ключей, строго меньших key
+    pub fn rank(&self, key: &K) -> usize {
+        self.count_below(key, false)
+    }
+
+    pub fn count_range<R: RangeBounds<K>>(&self, range: R) -> usize {
+        let before = match range.start_bound() {
+            Bound::Included(start) => self.count_below(start, false),
+            Bound::Excluded(start) => self.count_below(start, true),
+            Bound::Unbounded => 0,
+        };
+        let until = match range.end_bound() {
+            Bound::Included(end) => self.count_below(end, true),
+            Bound::Excluded(end) => self.count_below(end, false),
+            Bound::Unbounded => self.len(),
+        };
+        until.saturating_sub(before)
+    }
+
+    fn count_below(&self, key: &K, inclusive: bool) -> usize {
+        let mut count = 0;
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
+            if node.key < *key || (inclusive && node.key == *key) {
+                count += Self::size(&node.left) + 1;
+                current = node.right.as_deref();
+            } else {
+                current = node.left.as_deref();
+            }
+        }
+        count
+    }
+
     pub fn clear(&mut self) {
         self.root = None;
     }
@@ -35,6 +114,142 @@ where
         Self::insert_node(&mut self.root, key, value);
     }
 
+    pub fn get(&self, key: &K) -> Option<&V> {
+        Self::find_node(self.root.as_deref(), key).map(|node| &node.value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        Self::find_node_mut(self.root.as_deref_mut(), key).map(|node| &mut node.value)
+    }
+
+    pub fn get_key_value(&self, key: &K) -> Option<(&K, &V)> {
+        Self::find_node(self.root.as_deref(), key).map(|node| (&node.key, &node.value))
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        Self::find_node(self.root.as_deref(), key).is_some()
+    }
+
+    // Ключ ищется один раз, дальше обе ветки спускаются по запомненному пути без сравнений
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        let (path, found, pivot) = self.search(&key);
+        if !found {
+            return Entry::Vacant(VacantEntry {
+                map: self,
+                key,
+                path,
+                pivot,
+            });
+        }
+
+        let mut node = self.root.as_deref_mut().unwrap();
+        for side in path {
+            node = Self::child_mut(node, side).as_deref_mut().unwrap();
+        }
+        Entry::Occupied(OccupiedEntry { node })
+    }
+
+    // Возвращает путь до ключа или до пустого места под него, признак того, что ключ
+    // найден, и глубину самого нижнего узла пути с ненулевым балансом
+    fn search(&self, key: &K) -> (Path, bool, Option<usize>) {
+        let mut path = Path::new();
+        let mut pivot = None;
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
+            let side = match key.cmp(&node.key) {
+                cmp::Ordering::Less => Side::Left,
+                cmp::Ordering::Greater => Side::Right,
+                cmp::Ordering::Equal => return (path, true, pivot),
+            };
+            if Self::height(&node.left) != Self::height(&node.right) {
+                pivot = Some(path.len());
+            }
+            path.push(side);
+            current = match side {
+                Side::Left => node.left.as_deref(),
+                Side::Right => node.right.as_deref(),
+            };
+        }
+        (path, false, pivot)
+    }
+
+    fn child_mut(node: &mut Node<K, V>, side: Side) -> &mut Option<Box<Node<K, V>>> {
+        match side {
+            Side::Left => &mut node.left,
+            Side::Right => &mut node.right,
+        }
+    }
+
+    // Вставка нового листа за один спуск по пути из search. Выше опорного узла (pivot)
+    // высоты не меняются, а все узлы ниже него были сбалансированы и вырастают на единицу.
+    // Если опорный узел перевешивал в сторону вставки, поворот делается до спуска:
+    // высоты повёрнутых узлов уже такие, какими станут после вставки
+    fn insert_leaf<'a>(
+        slot: &'a mut Option<Box<Node<K, V>>>,
+        path: &[Side],
+        pivot: Option<usize>,
+        mut leaf: Box<Node<K, V>>,
+    ) -> &'a mut V {
+        let Some(pivot) = pivot else {
+            return &mut Self::walk_down(slot, path, true).insert(leaf).value;
+        };
+
+        let slot = Self::walk_down(slot, &path[..pivot], false);
+        let side = path[pivot];
+        let mut node = slot.take().unwrap();
+        let heavy = if Self::height(&node.right) > Self::height(&node.left) {
+            Side::Right
+        } else {
+            Side::Left
+        };
+
+        let double;
+        let (turns, rest) = if heavy != side {
+            (&path[pivot..=pivot], &path[pivot + 1..])
+        } else if path[pivot + 1] == side {
+            node = Self::rotate(node, side);
+            (&path[pivot..=pivot], &path[pivot + 2..])
+        } else if path.len() == pivot + 2 {
+            // Новый лист сам становится корнем поддерева из трёх узлов
+            *Self::child_mut(&mut leaf, side) = Self::child_mut(&mut node, side).take();
+            Self::update_node(&mut node);
+            *Self::child_mut(&mut leaf, side.opposite()) = Some(node);
+            Self::update_node(&mut leaf);
+            return &mut slot.insert(leaf).value;
+        } else {
+            let child = Self::child_mut(&mut node, side).take().unwrap();
+            *Self::child_mut(&mut node, side) = Some(Self::rotate(child, side.opposite()));
+            node = Self::rotate(node, side);
+
+            // Внук стал корнем, спуск продолжается через того из его новых детей,
+            // которому досталось поддерево со стороны вставки
+            let grandchild = path[pivot + 2];
+            double = [grandchild, grandchild.opposite()];
+            (&double[..], &path[pivot + 3..])
+        };
+
+        *slot = Some(node);
+        let slot = Self::walk_down(slot, turns, false);
+        &mut Self::walk_down(slot, rest, true).insert(leaf).value
+    }
+
+    // Проходит по пути, увеличивая размеры (и высоты, если grow) пройденных узлов
+    fn walk_down<'a>(
+        mut slot: &'a mut Option<Box<Node<K, V>>>,
+        path: &[Side],
+        grow: bool,
+    ) -> &'a mut Option<Box<Node<K, V>>> {
+        for &side in path {
+            let node = slot.as_mut().unwrap();
+            node.size += 1;
+            if grow {
+                node.height += 1;
+            }
+            slot = Self::child_mut(node, side);
+        }
+        slot
+    }
+
     fn height(node: &Option<Box<Node<K, V>>>) -> isize {
         node.as_ref().map_or(-1, |n| n.height)
     }
@@ -46,31 +261,37 @@ where
         }
     }
 
-    fn update_height(node: &mut Box<Node<K, V>>) {
+    fn size(node: &Option<Box<Node<K, V>>>) -> usize {
+        node.as_ref().map_or(0, |n| n.size)
+    }
+
+    // Пересчитывает высоту и размер поддерева по его потомкам
+    fn update_node(node: &mut Box<Node<K, V>>) {
         node.height = 1 + cmp::max(Self::height(&node.right), Self::height(&node.left));
+        node.size = 1 + Self::size(&node.left) + Self::size(&node.right);
     }
 
     fn rotate_left(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
         let mut new_root = node.right.take().unwrap();
         node.right = new_root.left.take();
-        Self::update_height(&mut node);
+        Self::update_node(&mut node);
         new_root.left = Some(node);
-        Self::update_height(&mut new_root);
+        Self::update_node(&mut new_root);
         new_root
     }
 
     fn rotate_right(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
         let mut new_root = node.left.take().unwrap();
         node.left = new_root.right.take();
-        Self::update_height(&mut node);
+        Self::update_node(&mut node);
         new_root.right = Some(node);
-        Self::update_height(&mut new_root);
+        Self::update_node(&mut new_root);
         new_root
     }
 
     fn balance(node: Option<Box<Node<K, V>>>) -> Option<Box<Node<K, V>>> {
         let mut node = node?;
-        let balance = Self::balance_factor(&Some(node.clone()));
+        let balance = Self::height(&node.right) - Self::height(&node.left);
 
         if balance > 1 {
             let right_balance = Self::balance_factor(&node.right);
@@ -91,6 +312,14 @@ where
         Some(node)
     }
 
+    // Поворот, после которого корнем становится потомок со стороны raised
+    fn rotate(node: Box<Node<K, V>>, raised: Side) -> Box<Node<K, V>> {
+        match raised {
+            Side::Left => Self::rotate_right(node),
+            Side::Right => Self::rotate_left(node),
+        }
+    }
+
     fn insert_node(node: &mut Option<Box<Node<K, V>>>, key: K, value: V) {
         match node {
             None => {
@@ -100,6 +329,7 @@ where
                     left: None,
                     right: None,
                     height: 0,
+                    size: 1,
                 }));
             }
             Some(n) => {
@@ -111,7 +341,7 @@ where
                     n.value = value;
                     return;
                 }
-                Self::update_height(n);
+                Self::update_node(n);
                 *node = Self::balance(node.take());
             }
         }
@@ -132,34 +362,58 @@ where
         }
     }
 
-    pub fn remove(&mut self, key: &K) {
-        Self::remove_node(&mut self.root, key);
+    fn find_node_mut<'a>(node: Option<&'a mut Node<K, V>>, key: &K) -> Option<&'a mut Node<K, V>> {
+        let n = node?;
+        if key < &n.key {
+            Self::find_node_mut(n.left.as_deref_mut(), key)
+        } else if key > &n.key {
+            Self::find_node_mut(n.right.as_deref_mut(), key)
+        } else {
+            Some(n)
+        }
     }
 
-    fn remove_node(node: &mut Option<Box<Node<K, V>>>, key: &K) {
-        if let Some(mut n) = node.take() {
-            if key < &n.key {
-                Self::remove_node(&mut n.left, key);
-            } else if key > &n.key {
-                Self::remove_node(&mut n.right, key);
-            } else {
-                if n.left.is_none() {
-                    *node = n.right;
-                    return;
-                } else if n.right.is_none() {
-                    *node = n.left;
-                    return;
-                } else {
-                    // Узел с двумя детьми
-                    let min_node = Self::find_min(&mut n.right);
-                    n.key = min_node.key.clone();
-                    n.value = min_node.value.clone();
-                    Self::remove_node(&mut n.right, &n.key);
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn remove_entry(&mut self, key: &K) -> Option<(K, V)> {
+        Self::remove_node(&mut self.root, key)
+    }
+
+    fn remove_node(node: &mut Option<Box<Node<K, V>>>, key: &K) -> Option<(K, V)> {
+        let mut n = node.take()?;
+        let removed = if key < &n.key {
+            Self::remove_node(&mut n.left, key)
+        } else if key > &n.key {
+            Self::remove_node(&mut n.right, key)
+        } else {
+            let Node {
+                key,
+                value,
+                left,
+                mut right,
+                ..
+            } = *n;
+
+            match (left, right.is_some()) {
+                (None, _) => *node = right,
+                (left, false) => *node = left,
+                (left, true) => {
+                    // Узел с двумя детьми: на его место переносится минимальный узел правого поддерева
+                    let mut min_node = Self::find_min(&mut right);
+                    min_node.left = left;
+                    min_node.right = right;
+                    Self::update_node(&mut min_node);
+                    *node = Self::balance(Some(min_node));
                 }
             }
-            Self::update_height(&mut n);
-            *node = Self::balance(Some(n));
-        }
+            return Some((key, value));
+        };
+
+        Self::update_node(&mut n);
+        *node = Self::balance(Some(n));
+        removed
     }
 
     fn find_min(node: &mut Option<Box<Node<K, V>>>) -> Box<Node<K, V>> {
@@ -169,82 +423,723 @@ where
             return current;
         }
         let min = Self::find_min(&mut current.left);
-        Self::update_height(&mut current);
+        Self::update_node(&mut current);
         *node = Self::balance(Some(current));
         min
     }
 
-    pub fn iter(&self) -> MapIterator<K, V> {
-        let mut iter = MapIterator { stack: Vec::new() };
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        self.root.as_ref()?;
+        let min = Self::find_min(&mut self.root);
+        Some((min.key, min.value))
+    }
+
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        self.root.as_ref()?;
+        let max = Self::find_max(&mut self.root);
+        Some((max.key, max.value))
+    }
+
+    fn find_max(node: &mut Option<Box<Node<K, V>>>) -> Box<Node<K, V>> {
+        let mut current = node.take().unwrap();
+        if current.right.is_none() {
+            *node = current.left.take();
+            return current;
+        }
+        let max = Self::find_max(&mut current.right);
+        Self::update_node(&mut current);
+        *node = Self::balance(Some(current));
+        max
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.first_from(Bound::Unbounded)
+    }
+
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.last_until(Bound::Unbounded)
+    }
+
+    // Наибольший ключ <= key
+    pub fn floor(&self, key: &K) -> Option<(&K, &V)> {
+        self.last_until(Bound::Included(key))
+    }
+
+    // Наименьший ключ >= key
+    pub fn ceiling(&self, key: &K) -> Option<(&K, &V)> {
+        self.first_from(Bound::Included(key))
+    }
+
+    // Наибольший ключ < key
+    pub fn predecessor(&self, key: &K) -> Option<(&K, &V)> {
+        self.last_until(Bound::Excluded(key))
+    }
+
+    // Наименьший ключ > key
+    pub fn successor(&self, key: &K) -> Option<(&K, &V)> {
+        self.first_from(Bound::Excluded(key))
+    }
+
+    // Итератор с первого ключа >= key
+    pub fn lower_bound(&self, key: &K) -> Range<'_, K, V> {
+        self.range((Bound::Included(key), Bound::Unbounded))
+    }
+
+    // Итератор с первого ключа > key
+    pub fn upper_bound(&self, key: &K) -> Range<'_, K, V> {
+        self.range((Bound::Excluded(key), Bound::Unbounded))
+    }
+
+    fn first_from(&self, start: Bound<&K>) -> Option<(&K, &V)> {
+        let mut found = None;
         let mut current = self.root.as_deref();
         while let Some(node) = current {
-            iter.stack.push(node.clone());
-            current = node.left.as_deref();
+            if Self::below_start(start, &node.key) {
+                current = node.right.as_deref();
+            } else {
+                found = Some((&node.key, &node.value));
+                current = node.left.as_deref();
+            }
+        }
+        found
+    }
+
+    fn last_until(&self, end: Bound<&K>) -> Option<(&K, &V)> {
+        let mut found = None;
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
+            if Self::above_end(end, &node.key) {
+                current = node.left.as_deref();
+            } else {
+                found = Some((&node.key, &node.value));
+                current = node.right.as_deref();
+            }
+        }
+        found
+    }
+
+    // Все ключи left должны быть меньше key, а ключи right - больше
+    pub fn join(left: Self, key: K, value: V, right: Self) -> Self {
+        assert!(
+            left.last_key_value().is_none_or(|(last, _)| *last < key)
+                && right
+                    .first_key_value()
+                    .is_none_or(|(first, _)| key < *first),
+            "join requires left < key < right"
+        );
+        let mid = Box::new(Node {
+            key,
+            value,
+            left: None,
+            right: None,
+            height: 0,
+            size: 1,
+        });
+        Map {
+            root: Some(Self::join_nodes(left.root, mid, right.root)),
+        }
+    }
+
+    // Отделяет в новый Map все ключи >= key
+    pub fn split_off(&mut self, key: &K) -> Self {
+        let (left, found, right) = Self::split(self.root.take(), key);
+        self.root = left;
+        let right = match found {
+            Some(found) => Some(Self::join_nodes(None, found, right)),
+            None => right,
+        };
+        Map { root: right }
+    }
+
+    // Переносит все записи other в self, при совпадении ключей побеждает other
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            std::mem::swap(self, other);
+            return;
+        }
+
+        // Непересекающиеся диапазоны ключей склеиваются за O(log n)
+        let before = self.last_key_value().unwrap().0 < other.first_key_value().unwrap().0;
+        let after = other.last_key_value().unwrap().0 < self.first_key_value().unwrap().0;
+        let (mine, theirs) = (self.root.take(), other.root.take());
+        self.root = if before {
+            Self::join_two(mine, theirs)
+        } else if after {
+            Self::join_two(theirs, mine)
+        } else {
+            Self::union_nodes(mine, theirs)
+        };
+    }
+
+    // Ключи обоих Map, при совпадении остаётся значение из other
+    pub fn union(self, other: Self) -> Self {
+        Map {
+            root: Self::union_nodes(self.root, other.root),
+        }
+    }
+
+    // Ключи, которые есть в обоих Map, со значениями из self
+    pub fn intersection(self, other: Self) -> Self {
+        Map {
+            root: Self::intersection_nodes(self.root, other.root),
+        }
+    }
+
+    // Ключи self, которых нет в other
+    pub fn difference(self, other: Self) -> Self {
+        Map {
+            root: Self::difference_nodes(self.root, other.root),
+        }
+    }
+
+    // Ключи, которые есть ровно в одном из Map
+    pub fn symmetric_difference(self, other: Self) -> Self {
+        Map {
+            root: Self::symmetric_difference_nodes(self.root, other.root),
+        }
+    }
+
+    // Склеивает два дерева через средний узел, спускаясь по более высокому
+    // до поддерева подходящей высоты
+    fn join_nodes(
+        left: Link<K, V>,
+        mut mid: Box<Node<K, V>>,
+        right: Link<K, V>,
+    ) -> Box<Node<K, V>> {
+        let left_height = Self::height(&left);
+        let right_height = Self::height(&right);
+
+        if left_height > right_height + 1 {
+            let mut node = left.unwrap();
+            node.right = Some(Self::join_nodes(node.right.take(), mid, right));
+            Self::update_node(&mut node);
+            return Self::balance(Some(node)).unwrap();
+        }
+
+        if right_height > left_height + 1 {
+            let mut node = right.unwrap();
+            node.left = Some(Self::join_nodes(left, mid, node.left.take()));
+            Self::update_node(&mut node);
+            return Self::balance(Some(node)).unwrap();
+        }
+
+        mid.left = left;
+        mid.right = right;
+        Self::update_node(&mut mid);
+        mid
+    }
+
+    fn join_two(mut left: Link<K, V>, right: Link<K, V>) -> Link<K, V> {
+        if left.is_none() {
+            return right;
+        }
+        let max = Self::find_max(&mut left);
+        Some(Self::join_nodes(left, max, right))
+    }
+
+    // Делит дерево на ключи < key, узел с key (если есть) и ключи > key
+    fn split(node: Link<K, V>, key: &K) -> (Link<K, V>, Link<K, V>, Link<K, V>) {
+        let Some(mut node) = node else {
+            return (None, None, None);
+        };
+        let left = node.left.take();
+        let right = node.right.take();
+
+        if key < &node.key {
+            let (less, found, greater) = Self::split(left, key);
+            (less, found, Some(Self::join_nodes(greater, node, right)))
+        } else if key > &node.key {
+            let (less, found, greater) = Self::split(right, key);
+            (Some(Self::join_nodes(left, node, less)), found, greater)
+        } else {
+            (left, Some(node), right)
         }
-        iter
     }
 
-    pub fn find(&self, key: &K) -> Option<MapIterator<K, V>> {
-        let mut iter = MapIterator { stack: Vec::new() };
-        let mut current = Self::find_node(self.root.as_deref(), key);
+    // Объединение деревьев, при совпадении ключей остаётся узел из second
+    fn union_nodes(first: Link<K, V>, second: Link<K, V>) -> Link<K, V> {
+        let Some(mut node) = second else {
+            return first;
+        };
+        if first.is_none() {
+            return Some(node);
+        }
 
-        if current.is_none() {
+        let second_left = node.left.take();
+        let second_right = node.right.take();
+        let (first_left, _, first_right) = Self::split(first, &node.key);
+        let left = Self::union_nodes(first_left, second_left);
+        let right = Self::union_nodes(first_right, second_right);
+        Some(Self::join_nodes(left, node, right))
+    }
+
+    fn intersection_nodes(first: Link<K, V>, second: Link<K, V>) -> Link<K, V> {
+        let mut node = first?;
+        second.as_ref()?;
+
+        let first_left = node.left.take();
+        let first_right = node.right.take();
+        let (second_left, found, second_right) = Self::split(second, &node.key);
+        let left = Self::intersection_nodes(first_left, second_left);
+        let right = Self::intersection_nodes(first_right, second_right);
+        match found {
+            Some(_) => Some(Self::join_nodes(left, node, right)),
+            None => Self::join_two(left, right),
+        }
+    }
+
+    fn difference_nodes(first: Link<K, V>, second: Link<K, V>) -> Link<K, V> {
+        let Some(mut node) = second else {
+            return first;
+        };
+        first.as_ref()?;
+
+        let second_left = node.left.take();
+        let second_right = node.right.take();
+        let (first_left, _, first_right) = Self::split(first, &node.key);
+        let left = Self::difference_nodes(first_left, second_left);
+        let right = Self::difference_nodes(first_right, second_right);
+        Self::join_two(left, right)
+    }
+
+    fn symmetric_difference_nodes(first: Link<K, V>, second: Link<K, V>) -> Link<K, V> {
+        let Some(mut node) = first else {
+            return second;
+        };
+        if second.is_none() {
+            return Some(node);
+        }
+
+        let first_left = node.left.take();
+        let first_right = node.right.take();
+        let (second_left, found, second_right) = Self::split(second, &node.key);
+        let left = Self::symmetric_difference_nodes(first_left, second_left);
+        let right = Self::symmetric_difference_nodes(first_right, second_right);
+        match found {
+            Some(_) => Self::join_two(left, right),
+            None => Some(Self::join_nodes(left, node, right)),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.range(..)
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        self.range_mut(..)
+    }
+
+    pub fn find(&self, key: &K) -> Option<Iter<'_, K, V>> {
+        if !self.contains_key(key) {
             return None;
         }
+        Some(self.range((Bound::Included(key), Bound::Unbounded)))
+    }
+
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Range<'_, K, V> {
+        let mut pieces = VecDeque::new();
+        Self::collect_range(
+            self.root.as_deref(),
+            range.start_bound(),
+            range.end_bound(),
+            &mut pieces,
+        );
+        Range { pieces }
+    }
+
+    pub fn range_mut<R: RangeBounds<K>>(&mut self, range: R) -> RangeMut<'_, K, V> {
+        let mut pieces = VecDeque::new();
+        Self::collect_range_mut(
+            self.root.as_deref_mut(),
+            range.start_bound(),
+            range.end_bound(),
+            &mut pieces,
+        );
+        RangeMut { pieces }
+    }
+
+    fn below_start(start: Bound<&K>, key: &K) -> bool {
+        match start {
+            Bound::Included(start) => key < start,
+            Bound::Excluded(start) => key <= start,
+            Bound::Unbounded => false,
+        }
+    }
 
-        while let Some(node) = current {
-            iter.stack.push(node.clone());
-            current = node.left.as_deref();
+    fn above_end(end: Bound<&K>, key: &K) -> bool {
+        match end {
+            Bound::Included(end) => key > end,
+            Bound::Excluded(end) => key >= end,
+            Bound::Unbounded => false,
+        }
+    }
+
+    // Раскладывает диапазон на O(log n) кусков: отдельные записи на границах
+    // и целые поддеревья, которые полностью в него попадают
+    fn collect_range<'a>(
+        node: Option<&'a Node<K, V>>,
+        start: Bound<&K>,
+        end: Bound<&K>,
+        pieces: &mut VecDeque<Piece<'a, K, V>>,
+    ) {
+        let Some(n) = node else {
+            return;
+        };
+
+        if let (Bound::Unbounded, Bound::Unbounded) = (start, end) {
+            pieces.push_back(Piece::Tree(n));
+        } else if Self::below_start(start, &n.key) {
+            Self::collect_range(n.right.as_deref(), start, end, pieces);
+        } else if Self::above_end(end, &n.key) {
+            Self::collect_range(n.left.as_deref(), start, end, pieces);
+        } else {
+            Self::collect_range(n.left.as_deref(), start, Bound::Unbounded, pieces);
+            pieces.push_back(Piece::Entry(&n.key, &n.value));
+            Self::collect_range(n.right.as_deref(), Bound::Unbounded, end, pieces);
+        }
+    }
+
+    fn collect_range_mut<'a>(
+        node: Option<&'a mut Node<K, V>>,
+        start: Bound<&K>,
+        end: Bound<&K>,
+        pieces: &mut VecDeque<PieceMut<'a, K, V>>,
+    ) {
+        let Some(n) = node else {
+            return;
+        };
+
+        if let (Bound::Unbounded, Bound::Unbounded) = (start, end) {
+            pieces.push_back(PieceMut::Tree(n));
+        } else if Self::below_start(start, &n.key) {
+            Self::collect_range_mut(n.right.as_deref_mut(), start, end, pieces);
+        } else if Self::above_end(end, &n.key) {
+            Self::collect_range_mut(n.left.as_deref_mut(), start, end, pieces);
+        } else {
+            let Node {
+                key,
+                value,
+                left,
+                right,
+                ..
+            } = n;
+            Self::collect_range_mut(left.as_deref_mut(), start, Bound::Unbounded, pieces);
+            pieces.push_back(PieceMut::Entry(key, value));
+            Self::collect_range_mut(right.as_deref_mut(), Bound::Unbounded, end, pieces);
         }
-        Some(iter)
     }
 }
 
-pub struct MapIterator<K: Ord, V: Clone> {
-    stack: Vec<Node<K, V>>,
+pub enum Entry<'a, K: Ord, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
 }
 
-impl<K, V> Iterator for MapIterator<K, V>
-where
-    K: Ord + Clone,
-    V: Clone,
-{
-    type Item = (K, V);
+pub struct OccupiedEntry<'a, K: Ord, V> {
+    node: &'a mut Node<K, V>,
+}
+
+pub struct VacantEntry<'a, K: Ord, V> {
+    map: &'a mut Map<K, V>,
+    key: K,
+    path: Path,
+    pivot: Option<usize>,
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.node.key
+    }
+
+    pub fn get(&self) -> &V {
+        &self.node.value
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.node.value
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.node.value
+    }
+
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(&mut self.node.value, value)
+    }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn insert(self, value: V) -> &'a mut V {
+        let leaf = Box::new(Node {
+            key: self.key,
+            value,
+            left: None,
+            right: None,
+            height: 0,
+            size: 1,
+        });
+        Map::insert_leaf(&mut self.map.root, &self.path, self.pivot, leaf)
+    }
+}
+
+// Ещё не выданная часть диапазона: запись или целое поддерево, которое раскрывается
+// с того конца, с которого к нему подошли
+enum Piece<'a, K: Ord, V> {
+    Entry(&'a K, &'a V),
+    Tree(&'a Node<K, V>),
+}
+
+pub struct Range<'a, K: Ord, V> {
+    pieces: VecDeque<Piece<'a, K, V>>,
+}
+
+pub type Iter<'a, K, V> = Range<'a, K, V>;
+
+impl<'a, K: Ord, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(node) = self.stack.pop() {
-            let result = (node.key.clone(), node.value.clone());
+        loop {
+            match self.pieces.pop_front()? {
+                Piece::Entry(key, value) => return Some((key, value)),
+                Piece::Tree(node) => {
+                    if let Some(right) = node.right.as_deref() {
+                        self.pieces.push_front(Piece::Tree(right));
+                    }
+                    self.pieces.push_front(Piece::Entry(&node.key, &node.value));
+                    if let Some(left) = node.left.as_deref() {
+                        self.pieces.push_front(Piece::Tree(left));
+                    }
+                }
+            }
+        }
+    }
+}
 
-            let mut current = node.right.as_deref();
-            while let Some(next_node) = current {
-                self.stack.push(next_node.clone());
-                current = next_node.left.as_deref();
+impl<K: Ord, V> DoubleEndedIterator for Range<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.pieces.pop_back()? {
+                Piece::Entry(key, value) => return Some((key, value)),
+                Piece::Tree(node) => {
+                    if let Some(left) = node.left.as_deref() {
+                        self.pieces.push_back(Piece::Tree(left));
+                    }
+                    self.pieces.push_back(Piece::Entry(&node.key, &node.value));
+                    if let Some(right) = node.right.as_deref() {
+                        self.pieces.push_back(Piece::Tree(right));
+                    }
+                }
             }
+        }
+    }
+}
 
-            Some(result)
-        } else {
-            None
+// Узел разбирается на независимые заимствования ключа, значения и поддеревьев,
+// поэтому куски никогда не пересекаются
+enum PieceMut<'a, K: Ord, V> {
+    Entry(&'a K, &'a mut V),
+    Tree(&'a mut Node<K, V>),
+}
+
+pub struct RangeMut<'a, K: Ord, V> {
+    pieces: VecDeque<PieceMut<'a, K, V>>,
+}
+
+pub type IterMut<'a, K, V> = RangeMut<'a, K, V>;
+
+impl<'a, K: Ord, V> Iterator for RangeMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.pieces.pop_front()? {
+                PieceMut::Entry(key, value) => return Some((key, value)),
+                PieceMut::Tree(node) => {
+                    let Node {
+                        key,
+                        value,
+                        left,
+                        right,
+                        ..
+                    } = node;
+                    if let Some(right) = right.as_deref_mut() {
+                        self.pieces.push_front(PieceMut::Tree(right));
+                    }
+                    self.pieces.push_front(PieceMut::Entry(key, value));
+                    if let Some(left) = left.as_deref_mut() {
+                        self.pieces.push_front(PieceMut::Tree(left));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<K: Ord, V> DoubleEndedIterator for RangeMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.pieces.pop_back()? {
+                PieceMut::Entry(key, value) => return Some((key, value)),
+                PieceMut::Tree(node) => {
+                    let Node {
+                        key,
+                        value,
+                        left,
+                        right,
+                        ..
+                    } = node;
+                    if let Some(left) = left.as_deref_mut() {
+                        self.pieces.push_back(PieceMut::Tree(left));
+                    }
+                    self.pieces.push_back(PieceMut::Entry(key, value));
+                    if let Some(right) = right.as_deref_mut() {
+                        self.pieces.push_back(PieceMut::Tree(right));
+                    }
+                }
+            }
+        }
+    }
+}
+
+enum OwnedPiece<K: Ord, V> {
+    Entry(K, V),
+    Tree(Box<Node<K, V>>),
+}
+
+pub struct IntoIter<K: Ord, V> {
+    pieces: VecDeque<OwnedPiece<K, V>>,
+}
+
+impl<K: Ord, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.pieces.pop_front()? {
+                OwnedPiece::Entry(key, value) => return Some((key, value)),
+                OwnedPiece::Tree(node) => {
+                    let Node {
+                        key,
+                        value,
+                        left,
+                        right,
+                        ..
+                    } = *node;
+                    if let Some(right) = right {
+                        self.pieces.push_front(OwnedPiece::Tree(right));
+                    }
+                    self.pieces.push_front(OwnedPiece::Entry(key, value));
+                    if let Some(left) = left {
+                        self.pieces.push_front(OwnedPiece::Tree(left));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<K: Ord, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.pieces.pop_back()? {
+                OwnedPiece::Entry(key, value) => return Some((key, value)),
+                OwnedPiece::Tree(node) => {
+                    let Node {
+                        key,
+                        value,
+                        left,
+                        right,
+                        ..
+                    } = *node;
+                    if let Some(left) = left {
+                        self.pieces.push_back(OwnedPiece::Tree(left));
+                    }
+                    self.pieces.push_back(OwnedPiece::Entry(key, value));
+                    if let Some(right) = right {
+                        self.pieces.push_back(OwnedPiece::Tree(right));
+                    }
+                }
+            }
         }
     }
 }
 
 impl<K, V> IntoIterator for Map<K, V>
 where
-    K: Ord + Clone,
-    V: Clone,
+    K: Ord,
 {
     type Item = (K, V);
-    type IntoIter = MapIterator<K, V>;
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            pieces: self.root.map(OwnedPiece::Tree).into_iter().collect(),
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a Map<K, V>
+where
+    K: Ord,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.iter()
     }
 }
 
+impl<'a, K, V> IntoIterator for &'a mut Map<K, V>
+where
+    K: Ord,
+{
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
 impl<K, V> Index<K> for Map<K, V>
 where
-    K: Ord + Clone,
-    V: Clone,
+    K: Ord,
 {
     type Output = V;
 
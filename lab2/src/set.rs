@@ -0,0 +1,125 @@
+use std::ops::RangeBounds;
+
+use crate::map::{self, Entry, Map};
+
+// Упорядоченное множество поверх AVL-дерева Map с пустыми значениями
+#[derive(Debug, Clone)]
+pub struct Set<K: Ord> {
+    map: Map<K, ()>,
+}
+
+impl<K> Set<K>
+where
+    K: Ord,
+{
+    pub fn new() -> Self {
+        Set { map: Map::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    // Возвращает false, если ключ уже был в множестве
+    pub fn insert(&mut self, key: K) -> bool {
+        match self.map.entry(key) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(());
+                true
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &K) -> bool {
+        self.map.remove(key).is_some()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    pub fn first(&self) -> Option<&K> {
+        self.map.first_key_value().map(|(key, _)| key)
+    }
+
+    pub fn last(&self) -> Option<&K> {
+        self.map.last_key_value().map(|(key, _)| key)
+    }
+
+    pub fn iter(&self) -> Iter<'_, K> {
+        self.range(..)
+    }
+
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Range<'_, K> {
+        Range {
+            inner: self.map.range(range),
+        }
+    }
+
+    // Операции ниже разбивают одно дерево по корню другого и склеивают
+    // результаты рекурсии через join, а не вставляют элементы по одному
+    pub fn union(self, other: Self) -> Self {
+        Set {
+            map: self.map.union(other.map),
+        }
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Set {
+            map: self.map.intersection(other.map),
+        }
+    }
+
+    pub fn difference(self, other: Self) -> Self {
+        Set {
+            map: self.map.difference(other.map),
+        }
+    }
+
+    pub fn symmetric_difference(self, other: Self) -> Self {
+        Set {
+            map: self.map.symmetric_difference(other.map),
+        }
+    }
+}
+
+pub struct Range<'a, K: Ord> {
+    inner: map::Range<'a, K, ()>,
+}
+
+pub type Iter<'a, K> = Range<'a, K>;
+
+impl<'a, K: Ord> Iterator for Range<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+}
+
+impl<K: Ord> DoubleEndedIterator for Range<'_, K> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<'a, K> IntoIterator for &'a Set<K>
+where
+    K: Ord,
+{
+    type Item = &'a K;
+    type IntoIter = Iter<'a, K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}